@@ -0,0 +1,134 @@
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::io::Error as IoError;
+
+use futures_core::{ready, Stream};
+use futures_io::{AsyncBufRead, AsyncRead};
+use pin_project_lite::pin_project;
+
+use crate::Decoder;
+
+//
+pin_project! {
+    /// Reader for the [`Decoder::into_async_read()`] method. See method docs for details.
+    #[derive(Debug)]
+    pub struct DecoderReader<R> {
+        #[pin]
+        inner: Decoder<R>,
+        chunk: Vec<u8>,
+        pos: usize,
+    }
+}
+
+impl<R: AsyncRead> DecoderReader<R> {
+    pub(crate) fn new(inner: Decoder<R>) -> Self {
+        Self {
+            inner,
+            chunk: vec![],
+            pos: 0,
+        }
+    }
+
+    pub fn get_ref(&self) -> &Decoder<R> {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut Decoder<R> {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> Decoder<R> {
+        self.inner
+    }
+}
+
+//
+impl<R: AsyncRead> AsyncBufRead for DecoderReader<R> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<&[u8], IoError>> {
+        let mut this = self.project();
+
+        // Zero-length frames carry no payload, keep reading.
+        while *this.pos >= this.chunk.len() {
+            match ready!(this.inner.as_mut().poll_next(cx)) {
+                Some(Ok(chunk)) => {
+                    *this.chunk = chunk;
+                    *this.pos = 0;
+                }
+                Some(Err(err)) => return Poll::Ready(Err(err)),
+                None => return Poll::Ready(Ok(&[])),
+            }
+        }
+
+        Poll::Ready(Ok(&this.chunk[*this.pos..]))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        let this = self.project();
+
+        *this.pos = core::cmp::min(*this.pos + amt, this.chunk.len());
+    }
+}
+
+impl<R: AsyncRead> AsyncRead for DecoderReader<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize, IoError>> {
+        let chunk = ready!(self.as_mut().poll_fill_buf(cx))?;
+
+        let n = core::cmp::min(chunk.len(), buf.len());
+        buf[..n].copy_from_slice(&chunk[..n]);
+
+        self.consume(n);
+
+        Poll::Ready(Ok(n))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures_util::{
+        io::{AsyncBufReadExt as _, AsyncReadExt as _, Cursor},
+        SinkExt as _,
+    };
+
+    use crate::Encoder;
+
+    #[test]
+    fn simple() -> Result<(), Box<dyn std::error::Error>> {
+        futures_executor::block_on(async {
+            let mut encoder = Encoder::new(Cursor::new(vec![]));
+            encoder.send(&"ab").await?;
+            encoder.send(&[]).await?;
+            encoder.send(&"c\nde").await?;
+            encoder.send(&"f\n").await?;
+
+            let mut cursor = encoder.into_inner();
+            cursor.set_position(0);
+
+            let mut r = Decoder::new(cursor).into_async_read();
+            let mut line = String::new();
+            r.read_line(&mut line).await?;
+            assert_eq!(line, "abc\n");
+
+            let mut buf = vec![];
+            r.read_to_end(&mut buf).await?;
+            assert_eq!(buf, b"def\n");
+
+            let mut r = Decoder::new(Cursor::new(vec![0, 0, 0])).into_async_read();
+            let err = r
+                .read_to_end(&mut vec![])
+                .await
+                .err()
+                .ok_or("should fail")?;
+            assert!(err.to_string().contains("need more head, n:5"));
+
+            Ok(())
+        })
+    }
+}
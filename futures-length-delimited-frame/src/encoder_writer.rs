@@ -0,0 +1,137 @@
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::io::Error as IoError;
+
+use futures_core::ready;
+use futures_io::AsyncWrite;
+use futures_sink::Sink;
+use pin_project_lite::pin_project;
+
+use crate::Encoder;
+
+//
+pin_project! {
+    /// Writer for the [`Encoder::into_async_write()`] method. See method docs for details.
+    #[derive(Debug)]
+    pub struct EncoderWriter<W> {
+        #[pin]
+        inner: Encoder<W>,
+        frame: Vec<u8>,
+        max_frame_len: usize,
+    }
+}
+
+impl<W: AsyncWrite> EncoderWriter<W> {
+    pub(crate) fn new(inner: Encoder<W>, max_frame_len: usize) -> Self {
+        assert!(max_frame_len > 0, "max_frame_len must be greater than 0");
+
+        Self {
+            inner,
+            frame: Vec::with_capacity(max_frame_len),
+            max_frame_len,
+        }
+    }
+
+    pub fn get_ref(&self) -> &Encoder<W> {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut Encoder<W> {
+        &mut self.inner
+    }
+
+    /// Bytes written but not yet framed are lost, call `close` or `flush` first.
+    pub fn into_inner(self) -> Encoder<W> {
+        self.inner
+    }
+
+    fn poll_send_frame(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), IoError>> {
+        let mut this = self.project();
+
+        if this.frame.is_empty() {
+            return Poll::Ready(Ok(()));
+        }
+
+        ready!(<Encoder<W> as Sink<&[u8]>>::poll_ready(
+            this.inner.as_mut(),
+            cx
+        ))?;
+        <Encoder<W> as Sink<&[u8]>>::start_send(this.inner.as_mut(), &this.frame[..])?;
+        this.frame.clear();
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+//
+impl<W: AsyncWrite> AsyncWrite for EncoderWriter<W> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<Result<usize, IoError>> {
+        if self.frame.len() >= self.max_frame_len {
+            ready!(self.as_mut().poll_send_frame(cx))?;
+        }
+
+        let this = self.project();
+
+        let n = core::cmp::min(buf.len(), *this.max_frame_len - this.frame.len());
+        this.frame.extend_from_slice(&buf[..n]);
+
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), IoError>> {
+        ready!(self.as_mut().poll_send_frame(cx))?;
+
+        let this = self.project();
+        <Encoder<W> as Sink<&[u8]>>::poll_flush(this.inner, cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), IoError>> {
+        ready!(self.as_mut().poll_send_frame(cx))?;
+
+        let this = self.project();
+        <Encoder<W> as Sink<&[u8]>>::poll_close(this.inner, cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures_util::{
+        io::{AsyncWriteExt as _, Cursor},
+        StreamExt as _,
+    };
+
+    use crate::Decoder;
+
+    #[test]
+    fn simple() -> Result<(), Box<dyn std::error::Error>> {
+        futures_executor::block_on(async {
+            let mut w = Encoder::new(Cursor::new(vec![])).into_async_write(4);
+            w.write_all(b"abcdefghij").await?;
+            w.flush().await?;
+            w.write_all(b"k").await?;
+            w.write_all(b"lm").await?;
+            w.close().await?;
+
+            let mut cursor = w.into_inner().into_inner();
+            cursor.set_position(0);
+
+            let frames = Decoder::new(cursor)
+                .map(|x| x.map(|x| String::from_utf8_lossy(&x).to_string()))
+                .collect::<Vec<_>>()
+                .await
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(frames, vec!["abcd", "efgh", "ij", "klm"]);
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,249 @@
+use std::io::{Error as IoError, ErrorKind as IoErrorKind};
+
+//
+/// How the length of every frame is written in front of its data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HeadFormat {
+    /// 8 byte big-endian `u64`, e.g. `[0, 0, 0, 0, 0, 0, 0, 3]abc`.
+    #[default]
+    U64Be,
+    /// ASCII decimal length terminated by `delimiter`, e.g. `3\nabc` with `b'\n'`.
+    Decimal { delimiter: u8 },
+    /// ASCII decimal length terminated by `:`, with a `,` after the data, e.g. `3:abc,`.
+    Netstring,
+    /// Unsigned LEB128 varint, e.g. `[3]abc`.
+    Leb128,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Head {
+    Complete { head_len: usize, data_len: u64 },
+    Partial { n_more: usize },
+}
+
+impl HeadFormat {
+    pub(crate) fn max_head_len(&self) -> usize {
+        match self {
+            Self::U64Be => U64_LEN,
+            // u64::MAX has 20 digits.
+            Self::Decimal { .. } | Self::Netstring => 20 + 1,
+            // 64 bits in 7 bit groups.
+            Self::Leb128 => 10,
+        }
+    }
+
+    pub(crate) fn trailer(&self) -> &'static [u8] {
+        match self {
+            Self::Netstring => b",",
+            Self::U64Be | Self::Decimal { .. } | Self::Leb128 => b"",
+        }
+    }
+
+    pub(crate) fn decode_head(&self, buf: &[u8]) -> Result<Head, IoError> {
+        match self {
+            Self::U64Be => {
+                if buf.len() >= U64_LEN {
+                    Ok(Head::Complete {
+                        head_len: U64_LEN,
+                        data_len: u64::from_be_bytes(buf[..U64_LEN].try_into().expect("Never")),
+                    })
+                } else {
+                    Ok(Head::Partial {
+                        n_more: U64_LEN - buf.len(),
+                    })
+                }
+            }
+            Self::Decimal { delimiter } => self.decode_decimal_head(buf, *delimiter),
+            Self::Netstring => self.decode_decimal_head(buf, b':'),
+            Self::Leb128 => {
+                let mut data_len: u64 = 0;
+                for (i, b) in buf.iter().take(self.max_head_len()).enumerate() {
+                    let bits = u64::from(b & 0x7f);
+                    if i == self.max_head_len() - 1 && bits > 1 {
+                        return Err(invalid_head("varint overflow"));
+                    }
+                    data_len |= bits << (7 * i);
+
+                    if b & 0x80 == 0 {
+                        return Ok(Head::Complete {
+                            head_len: i + 1,
+                            data_len,
+                        });
+                    }
+                }
+
+                if buf.len() >= self.max_head_len() {
+                    Err(invalid_head("varint overflow"))
+                } else {
+                    Ok(Head::Partial { n_more: 1 })
+                }
+            }
+        }
+    }
+
+    fn decode_decimal_head(&self, buf: &[u8], delimiter: u8) -> Result<Head, IoError> {
+        let mut data_len: u64 = 0;
+        for (i, b) in buf.iter().take(self.max_head_len()).enumerate() {
+            if *b == delimiter {
+                if i == 0 {
+                    return Err(invalid_head("no digit"));
+                }
+                return Ok(Head::Complete {
+                    head_len: i + 1,
+                    data_len,
+                });
+            }
+
+            if !b.is_ascii_digit() {
+                return Err(invalid_head(format!("not a digit, byte:{b}")));
+            }
+            data_len = data_len
+                .checked_mul(10)
+                .and_then(|x| x.checked_add(u64::from(b - b'0')))
+                .ok_or_else(|| invalid_head("decimal overflow"))?;
+        }
+
+        if buf.len() >= self.max_head_len() {
+            Err(invalid_head("no delimiter"))
+        } else {
+            Ok(Head::Partial { n_more: 1 })
+        }
+    }
+
+    pub(crate) fn encode_head(&self, data_len: u64, buf: &mut Vec<u8>) {
+        match self {
+            Self::U64Be => buf.extend_from_slice(data_len.to_be_bytes().as_ref()),
+            Self::Decimal { delimiter } => {
+                buf.extend_from_slice(data_len.to_string().as_bytes());
+                buf.push(*delimiter);
+            }
+            Self::Netstring => {
+                buf.extend_from_slice(data_len.to_string().as_bytes());
+                buf.push(b':');
+            }
+            Self::Leb128 => {
+                let mut n = data_len;
+                loop {
+                    let b = (n & 0x7f) as u8;
+                    n >>= 7;
+                    if n == 0 {
+                        buf.push(b);
+                        break;
+                    }
+                    buf.push(b | 0x80);
+                }
+            }
+        }
+    }
+}
+
+const U64_LEN: usize = core::mem::size_of::<u64>();
+
+fn invalid_head(msg: impl core::fmt::Display) -> IoError {
+    IoError::new(IoErrorKind::InvalidData, format!("invalid head, {msg}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_head() -> Result<(), Box<dyn std::error::Error>> {
+        for (format, buf, head) in [
+            (
+                HeadFormat::U64Be,
+                &b"\0\0\0"[..],
+                Head::Partial { n_more: 5 },
+            ),
+            (
+                HeadFormat::U64Be,
+                &[0, 0, 0, 0, 0, 0, 1, 0, 97],
+                Head::Complete {
+                    head_len: 8,
+                    data_len: 256,
+                },
+            ),
+            (
+                HeadFormat::Decimal { delimiter: b'\n' },
+                b"12",
+                Head::Partial { n_more: 1 },
+            ),
+            (
+                HeadFormat::Decimal { delimiter: b'\n' },
+                b"12\nab",
+                Head::Complete {
+                    head_len: 3,
+                    data_len: 12,
+                },
+            ),
+            (
+                HeadFormat::Netstring,
+                b"0:,",
+                Head::Complete {
+                    head_len: 2,
+                    data_len: 0,
+                },
+            ),
+            (HeadFormat::Leb128, &[0x80], Head::Partial { n_more: 1 }),
+            (
+                HeadFormat::Leb128,
+                &[0xe5, 0x8e, 0x26, 0],
+                Head::Complete {
+                    head_len: 3,
+                    data_len: 624485,
+                },
+            ),
+            (
+                HeadFormat::Leb128,
+                &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01],
+                Head::Complete {
+                    head_len: 10,
+                    data_len: u64::MAX,
+                },
+            ),
+        ] {
+            assert_eq!(format.decode_head(buf)?, head, "{format:?} {buf:?}");
+        }
+
+        for (format, buf, msg) in [
+            (HeadFormat::Netstring, &b":abc"[..], "no digit"),
+            (HeadFormat::Netstring, b"1a:", "not a digit, byte:97"),
+            (
+                HeadFormat::Netstring,
+                b"99999999999999999999:",
+                "decimal overflow",
+            ),
+            (
+                HeadFormat::Decimal { delimiter: b'\n' },
+                b"000000000000000000000",
+                "no delimiter",
+            ),
+            (
+                HeadFormat::Leb128,
+                &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02],
+                "varint overflow",
+            ),
+        ] {
+            let err = format.decode_head(buf).err().ok_or("should fail")?;
+            assert_eq!(err.kind(), IoErrorKind::InvalidData);
+            assert!(err.to_string().contains(msg), "{format:?} {err}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_encode_head() {
+        for (format, data_len, head) in [
+            (HeadFormat::U64Be, 3, &[0, 0, 0, 0, 0, 0, 0, 3][..]),
+            (HeadFormat::Decimal { delimiter: b'\n' }, 12, b"12\n"),
+            (HeadFormat::Netstring, 0, b"0:"),
+            (HeadFormat::Leb128, 624485, &[0xe5, 0x8e, 0x26]),
+            (HeadFormat::Leb128, 127, &[0x7f]),
+        ] {
+            let mut buf = vec![];
+            format.encode_head(data_len, &mut buf);
+            assert_eq!(buf, head, "{format:?}");
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use core::{future::Future, time::Duration};
+use std::io::Error as IoError;
+
+use futures_core::Stream;
+use futures_io::AsyncWrite;
+use futures_util::{
+    future::{self, Either},
+    pin_mut, SinkExt, StreamExt as _,
+};
+
+use crate::Encoder;
+
+//
+impl<W: AsyncWrite + Unpin> Encoder<W> {
+    /// Send every item of `items`, and a zero-length frame whenever no item arrived for `interval`.
+    ///
+    /// `sleep` is the timer, e.g. `tokio::time::sleep` or `|dur| async_io::Timer::after(dur)`.
+    pub async fn send_all_with_heartbeat<St, T, S, Fut>(
+        &mut self,
+        items: St,
+        interval: Duration,
+        mut sleep: S,
+    ) -> Result<(), IoError>
+    where
+        St: Stream<Item = T>,
+        T: AsRef<[u8]>,
+        S: FnMut(Duration) -> Fut,
+        Fut: Future,
+    {
+        pin_mut!(items);
+
+        loop {
+            let timer = sleep(interval);
+            pin_mut!(timer);
+
+            match future::select(items.next(), timer).await {
+                Either::Left((Some(item), _)) => self.send(item).await?,
+                Either::Left((None, _)) => return Ok(()),
+                Either::Right(_) => SinkExt::<&[u8]>::send(self, &[]).await?,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use async_io::Timer;
+    use futures_util::{io::Cursor, stream};
+
+    use crate::Decoder;
+
+    #[test]
+    fn simple() -> Result<(), Box<dyn std::error::Error>> {
+        futures_executor::block_on(async {
+            let items = stream::iter(["a", "b"]).then(|item| async move {
+                if item == "b" {
+                    Timer::after(Duration::from_millis(250)).await;
+                }
+                item
+            });
+
+            let mut encoder = Encoder::new(Cursor::new(vec![]));
+            encoder
+                .send_all_with_heartbeat(items, Duration::from_millis(100), Timer::after)
+                .await?;
+
+            let mut cursor = encoder.into_inner();
+            cursor.set_position(0);
+
+            let frames = Decoder::new(cursor)
+                .collect::<Vec<_>>()
+                .await
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(frames.first().ok_or("no first")?, b"a");
+            assert_eq!(frames.last().ok_or("no last")?, b"b");
+            assert!(frames.len() > 2);
+            assert!(frames[1..frames.len() - 1].iter().all(|x| x.is_empty()));
+
+            Ok(())
+        })
+    }
+}
@@ -2,17 +2,40 @@ use core::{
     pin::Pin,
     task::{Context, Poll},
 };
-use std::io::{Error as IoError, ErrorKind as IoErrorKind};
+use std::{
+    io::{Error as IoError, ErrorKind as IoErrorKind},
+    time::Instant,
+};
 
 use futures_core::{ready, Stream};
 use futures_io::{AsyncRead, AsyncWrite};
 use futures_sink::Sink;
 use pin_project_lite::pin_project;
 
+mod decoder_reader;
+mod encoder_writer;
+mod head_format;
+#[cfg(feature = "heartbeat")]
+mod heartbeat;
+#[cfg(feature = "rpc")]
+pub mod rpc;
+
+pub use self::{
+    decoder_reader::DecoderReader, encoder_writer::EncoderWriter, head_format::HeadFormat,
+};
+
+use self::head_format::Head;
+
 //
 //
 //
 pin_project! {
+    /// Decode length-delimited frames from an [`AsyncRead`].
+    ///
+    /// # Cancel safety
+    ///
+    /// All progress is kept in the decoder, so dropping a `next()` future (e.g. in `select!`)
+    /// never loses or duplicates a frame, the next call resumes where the dropped one stopped.
     #[derive(Debug)]
     pub struct Decoder<R> {
         #[pin]
@@ -20,6 +43,10 @@ pin_project! {
         buf: Vec<u8>,
         n_read: usize,
         state: DecodeState,
+        head_format: HeadFormat,
+        max_frame_len: usize,
+        swallow_heartbeat: bool,
+        last_seen: Option<Instant>,
     }
 }
 
@@ -29,14 +56,51 @@ impl<R: AsyncRead> Decoder<R> {
     }
 
     pub fn with_capacity(cap: usize, inner: R) -> Self {
+        Self::with_capacity_and_head_format(cap, HeadFormat::default(), inner)
+    }
+
+    pub fn with_head_format(head_format: HeadFormat, inner: R) -> Self {
+        Self::with_capacity_and_head_format(1024, head_format, inner)
+    }
+
+    pub fn with_capacity_and_head_format(cap: usize, head_format: HeadFormat, inner: R) -> Self {
         Self {
             inner,
-            buf: vec![0; cap],
+            buf: vec![0; core::cmp::max(cap, head_format.max_head_len())],
             n_read: 0,
             state: DecodeState::Head,
+            head_format,
+            max_frame_len: usize::MAX,
+            swallow_heartbeat: false,
+            last_seen: None,
         }
     }
 
+    pub fn head_format(&self) -> HeadFormat {
+        self.head_format
+    }
+
+    /// A frame with more data is an [`IoErrorKind::InvalidData`] error, the length comes from the peer.
+    ///
+    /// Unlimited by default, set it when the peer is not trusted.
+    pub fn set_max_frame_len(&mut self, max_frame_len: usize) {
+        self.max_frame_len = max_frame_len;
+    }
+
+    pub fn max_frame_len(&self) -> usize {
+        self.max_frame_len
+    }
+
+    /// Treat zero-length frames as heartbeats, they are not yielded.
+    pub fn set_swallow_heartbeat(&mut self, swallow_heartbeat: bool) {
+        self.swallow_heartbeat = swallow_heartbeat;
+    }
+
+    /// When the last frame, heartbeats included, was decoded.
+    pub fn last_seen(&self) -> Option<Instant> {
+        self.last_seen
+    }
+
     pub fn get_ref(&self) -> &R {
         &self.inner
     }
@@ -48,6 +112,11 @@ impl<R: AsyncRead> Decoder<R> {
     pub fn into_inner(self) -> R {
         self.inner
     }
+
+    /// Expose the concatenated frame payloads as an [`AsyncRead`] and [`futures_io::AsyncBufRead`].
+    pub fn into_async_read(self) -> DecoderReader<R> {
+        DecoderReader::new(self)
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -62,33 +131,65 @@ impl<R: AsyncRead> Stream for Decoder<R> {
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         let mut this = self.project();
 
-        let field_len = core::mem::size_of::<u64>();
+        let trailer = this.head_format.trailer();
+        let mut n_more_head = 0;
 
         loop {
             match *this.state {
                 DecodeState::Head => {
-                    if *this.n_read >= field_len {
-                        let data_len =
-                            u64::from_be_bytes(this.buf[..field_len].try_into().expect("Never"));
+                    match this.head_format.decode_head(&this.buf[..*this.n_read]) {
+                        Ok(Head::Complete { head_len, data_len }) => {
+                            // The peer controls data_len.
+                            let frame_len = usize::try_from(data_len)
+                                .ok()
+                                .filter(|x| *x <= *this.max_frame_len)
+                                .and_then(|x| x.checked_add(trailer.len()));
+                            let Some(frame_len) = frame_len else {
+                                return Poll::Ready(Some(Err(IoError::new(
+                                    IoErrorKind::InvalidData,
+                                    format!(
+                                        "frame too long, data_len:{data_len} max_frame_len:{}",
+                                        this.max_frame_len
+                                    ),
+                                ))));
+                            };
+                            let data_len = frame_len - trailer.len();
+
+                            if this.buf.len() < frame_len {
+                                this.buf.resize(frame_len, 0);
+                            }
+                            this.buf.rotate_left(head_len);
+                            *this.n_read -= head_len;
 
-                        if this.buf.len() < data_len as usize {
-                            this.buf.resize(data_len as usize, 0);
+                            *this.state = DecodeState::Data(data_len);
+                            continue;
                         }
-                        this.buf.rotate_left(field_len);
-                        *this.n_read -= field_len;
-
-                        *this.state = DecodeState::Data(data_len as usize);
-                        continue;
+                        Ok(Head::Partial { n_more }) => n_more_head = n_more,
+                        Err(err) => return Poll::Ready(Some(Err(err))),
                     }
                 }
                 DecodeState::Data(data_len) => {
-                    if *this.n_read >= data_len {
+                    let frame_len = data_len + trailer.len();
+
+                    if *this.n_read >= frame_len {
+                        if &this.buf[data_len..frame_len] != trailer {
+                            return Poll::Ready(Some(Err(IoError::new(
+                                IoErrorKind::InvalidData,
+                                format!("invalid trailer, expect:{trailer:?}"),
+                            ))));
+                        }
+
                         let data = this.buf[..data_len].to_vec();
 
-                        this.buf.rotate_left(data_len);
-                        *this.n_read -= data_len;
+                        this.buf.rotate_left(frame_len);
+                        *this.n_read -= frame_len;
 
                         *this.state = DecodeState::Head;
+                        *this.last_seen = Some(Instant::now());
+
+                        if data.is_empty() && *this.swallow_heartbeat {
+                            continue;
+                        }
 
                         return Poll::Ready(Some(Ok(data)));
                     }
@@ -109,7 +210,7 @@ impl<R: AsyncRead> Stream for Decoder<R> {
                                 } else {
                                     return Poll::Ready(Some(Err(IoError::new(
                                         IoErrorKind::Other,
-                                        format!("need more head, n:{}", field_len - *this.n_read),
+                                        format!("need more head, n:{n_more_head}"),
                                     ))));
                                 }
                             }
@@ -122,7 +223,10 @@ impl<R: AsyncRead> Stream for Decoder<R> {
                                 } else {
                                     return Poll::Ready(Some(Err(IoError::new(
                                         IoErrorKind::Other,
-                                        format!("need more data, n:{}", data_len - *this.n_read),
+                                        format!(
+                                            "need more data, n:{}",
+                                            data_len + trailer.len() - *this.n_read
+                                        ),
                                     ))));
                                 }
                             }
@@ -143,11 +247,19 @@ impl<R: AsyncRead> Stream for Decoder<R> {
 //
 //
 pin_project! {
+    /// Encode length-delimited frames into an [`AsyncWrite`].
+    ///
+    /// # Cancel safety
+    ///
+    /// The bytes already written are tracked across `poll_flush` calls, so an abandoned flush
+    /// resumed later writes every byte of every frame exactly once.
     #[derive(Debug)]
     pub struct Encoder<W> {
         #[pin]
         inner: W,
         buf: Vec<u8>,
+        n_write: usize,
+        head_format: HeadFormat,
     }
 }
 
@@ -157,12 +269,26 @@ impl<W: AsyncWrite> Encoder<W> {
     }
 
     pub fn with_capacity(cap: usize, inner: W) -> Self {
+        Self::with_capacity_and_head_format(cap, HeadFormat::default(), inner)
+    }
+
+    pub fn with_head_format(head_format: HeadFormat, inner: W) -> Self {
+        Self::with_capacity_and_head_format(1024, head_format, inner)
+    }
+
+    pub fn with_capacity_and_head_format(cap: usize, head_format: HeadFormat, inner: W) -> Self {
         Self {
             inner,
             buf: Vec::with_capacity(cap),
+            n_write: 0,
+            head_format,
         }
     }
 
+    pub fn head_format(&self) -> HeadFormat {
+        self.head_format
+    }
+
     pub fn get_ref(&self) -> &W {
         &self.inner
     }
@@ -174,6 +300,13 @@ impl<W: AsyncWrite> Encoder<W> {
     pub fn into_inner(self) -> W {
         self.inner
     }
+
+    /// Expose an [`AsyncWrite`] that frames the written bytes, at most `max_frame_len` bytes per frame.
+    ///
+    /// A frame is sent once it is full or on `flush`/`close`.
+    pub fn into_async_write(self, max_frame_len: usize) -> EncoderWriter<W> {
+        EncoderWriter::new(self, max_frame_len)
+    }
 }
 
 // https://github.com/tokio-rs/tokio/blob/tokio-util-0.7.7/tokio-util/src/codec/framed_impl.rs#L253
@@ -194,8 +327,9 @@ impl<T: AsRef<[u8]>, W: AsyncWrite> Sink<T> for Encoder<W> {
         let data = item.as_ref();
         let data_len = data.len() as u64;
 
-        this.buf.extend_from_slice(data_len.to_be_bytes().as_ref());
+        this.head_format.encode_head(data_len, this.buf);
         this.buf.extend_from_slice(data);
+        this.buf.extend_from_slice(this.head_format.trailer());
 
         Ok(())
     }
@@ -203,16 +337,19 @@ impl<T: AsRef<[u8]>, W: AsyncWrite> Sink<T> for Encoder<W> {
     fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
         let mut this = self.project();
 
-        let mut n_write = 0;
-        while !this.buf[n_write..].is_empty() {
-            let n = ready!(this.inner.as_mut().poll_write(cx, &this.buf[n_write..]))?;
-            n_write += n;
+        while !this.buf[*this.n_write..].is_empty() {
+            let n = ready!(this
+                .inner
+                .as_mut()
+                .poll_write(cx, &this.buf[*this.n_write..]))?;
+            *this.n_write += n;
 
             if n == 0 {
                 return Poll::Ready(Err(IoErrorKind::WriteZero.into()));
             }
         }
         this.buf.clear();
+        *this.n_write = 0;
 
         ready!(this.inner.as_mut().poll_flush(cx))?;
 
@@ -233,7 +370,7 @@ impl<T: AsRef<[u8]>, W: AsyncWrite> Sink<T> for Encoder<W> {
 mod tests {
     use super::*;
 
-    use futures_util::{io::Cursor, SinkExt as _, StreamExt as _};
+    use futures_util::{io::Cursor, SinkExt, StreamExt as _};
 
     #[test]
     fn simple() -> Result<(), Box<dyn std::error::Error>> {
@@ -362,4 +499,238 @@ mod tests {
             Ok(())
         })
     }
+
+    #[test]
+    fn test_head_format() -> Result<(), Box<dyn std::error::Error>> {
+        futures_executor::block_on(async {
+            for head_format in [
+                HeadFormat::U64Be,
+                HeadFormat::Decimal { delimiter: b'\n' },
+                HeadFormat::Netstring,
+                HeadFormat::Leb128,
+            ] {
+                let w: Cursor<Vec<u8>> = Cursor::new(vec![]);
+                let mut encoder = Encoder::with_head_format(head_format, w);
+                encoder.send(&"abc").await?;
+                encoder.send(&[]).await?;
+                encoder.send(vec![1; 300]).await?;
+
+                let mut r = encoder.into_inner();
+                r.set_position(0);
+
+                let mut decoder = Decoder::with_capacity_and_head_format(0, head_format, r);
+                assert_eq!(
+                    decoder.next().await.ok_or("decoder.next() is_none")??,
+                    b"abc"
+                );
+                assert_eq!(decoder.next().await.ok_or("decoder.next() is_none")??, b"");
+                assert_eq!(
+                    decoder.next().await.ok_or("decoder.next() is_none")??,
+                    vec![1; 300]
+                );
+                assert!(decoder.next().await.is_none());
+            }
+
+            let w: Cursor<Vec<u8>> = Cursor::new(vec![]);
+            let mut encoder = Encoder::with_head_format(HeadFormat::Netstring, w);
+            encoder.send(&"abc").await?;
+            encoder.send(&[]).await?;
+            assert_eq!(encoder.into_inner().get_ref(), b"3:abc,0:,");
+
+            let r: Cursor<Vec<u8>> = Cursor::new(b"3:abc,2:de;".to_vec());
+            let mut decoder = Decoder::with_head_format(HeadFormat::Netstring, r);
+            assert_eq!(
+                decoder.next().await.ok_or("decoder.next() is_none")??,
+                b"abc"
+            );
+            match decoder.next().await {
+                Some(Err(err)) => {
+                    assert_eq!(err.kind(), IoErrorKind::InvalidData);
+                    assert!(err.to_string().contains("invalid trailer"));
+                }
+                x => panic!("{x:?}"),
+            };
+
+            let r: Cursor<Vec<u8>> = Cursor::new(b"3:ab".to_vec());
+            let mut decoder = Decoder::with_head_format(HeadFormat::Netstring, r);
+            match decoder.next().await {
+                Some(Err(err)) => {
+                    assert_eq!(err.kind(), IoErrorKind::Other);
+                    assert!(err.to_string().contains("need more data, n:2"));
+                }
+                x => panic!("{x:?}"),
+            };
+
+            let r: Cursor<Vec<u8>> = Cursor::new(b"12".to_vec());
+            let mut decoder =
+                Decoder::with_head_format(HeadFormat::Decimal { delimiter: b'\n' }, r);
+            match decoder.next().await {
+                Some(Err(err)) => {
+                    assert_eq!(err.kind(), IoErrorKind::Other);
+                    assert!(err.to_string().contains("need more head, n:1"));
+                }
+                x => panic!("{x:?}"),
+            };
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_decoder_max_frame_len() -> Result<(), Box<dyn std::error::Error>> {
+        futures_executor::block_on(async {
+            assert_eq!(Decoder::new(Cursor::new(b"")).max_frame_len(), usize::MAX);
+
+            for (head_format, bytes) in [
+                (HeadFormat::Netstring, &b"18446744073709551615:abc"[..]),
+                (
+                    HeadFormat::Decimal { delimiter: b'\n' },
+                    b"18446744073709551615\nabc",
+                ),
+                (HeadFormat::U64Be, &[0xff; 12]),
+                (HeadFormat::Netstring, b"11:abc"),
+            ] {
+                let mut decoder = Decoder::with_head_format(head_format, Cursor::new(bytes));
+                decoder.set_max_frame_len(10);
+                match decoder.next().await {
+                    Some(Err(err)) => {
+                        assert_eq!(err.kind(), IoErrorKind::InvalidData);
+                        assert!(err.to_string().starts_with("frame too long"));
+                    }
+                    x => panic!("{x:?}"),
+                }
+            }
+
+            let mut decoder =
+                Decoder::with_head_format(HeadFormat::Netstring, Cursor::new(b"10:0123456789,"));
+            decoder.set_max_frame_len(10);
+            assert_eq!(
+                decoder.next().await.ok_or("decoder.next() is_none")??,
+                b"0123456789"
+            );
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_decoder_swallow_heartbeat() -> Result<(), Box<dyn std::error::Error>> {
+        futures_executor::block_on(async {
+            let r: Cursor<Vec<u8>> = Cursor::new(vec![
+                0, 0, 0, 0, 0, 0, 0, 0, //
+                0, 0, 0, 0, 0, 0, 0, 1, //
+                1, //
+                0, 0, 0, 0, 0, 0, 0, 0, //
+            ]);
+            let mut decoder = Decoder::new(r);
+            decoder.set_swallow_heartbeat(true);
+            assert!(decoder.last_seen().is_none());
+
+            assert_eq!(decoder.next().await.ok_or("decoder.next() is_none")??, &[1]);
+            let last_seen = decoder.last_seen().ok_or("last_seen is_none")?;
+
+            assert!(decoder.next().await.is_none());
+            assert!(decoder.last_seen().ok_or("last_seen is_none")? >= last_seen);
+
+            Ok(())
+        })
+    }
+
+    // Every other poll is Pending, otherwise at most 3 bytes are read or written.
+    struct Choppy<T> {
+        inner: T,
+        pending: bool,
+    }
+
+    impl<T> Choppy<T> {
+        fn new(inner: T) -> Self {
+            Self {
+                inner,
+                pending: true,
+            }
+        }
+
+        fn poll_pending(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+            self.pending = !self.pending;
+            if self.pending {
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            } else {
+                Poll::Ready(())
+            }
+        }
+    }
+
+    impl<T: AsyncRead + Unpin> AsyncRead for Choppy<T> {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<Result<usize, IoError>> {
+            ready!(self.poll_pending(cx));
+            let n = core::cmp::min(buf.len(), 3);
+            Pin::new(&mut self.inner).poll_read(cx, &mut buf[..n])
+        }
+    }
+
+    impl<T: AsyncWrite + Unpin> AsyncWrite for Choppy<T> {
+        fn poll_write(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<Result<usize, IoError>> {
+            ready!(self.poll_pending(cx));
+            let n = core::cmp::min(buf.len(), 3);
+            Pin::new(&mut self.inner).poll_write(cx, &buf[..n])
+        }
+
+        fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), IoError>> {
+            Pin::new(&mut self.inner).poll_flush(cx)
+        }
+
+        fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), IoError>> {
+            Pin::new(&mut self.inner).poll_close(cx)
+        }
+    }
+
+    // Poll a fresh future once at a time and drop it when Pending, i.e. cancel it.
+    macro_rules! poll_until_ready {
+        ($fut:expr) => {{
+            use futures_util::FutureExt as _;
+
+            (0..1000)
+                .find_map(|_| $fut.now_or_never())
+                .expect("never ready")
+        }};
+    }
+
+    #[test]
+    fn test_cancel_safety() -> Result<(), Box<dyn std::error::Error>> {
+        let mut encoder = Encoder::new(Choppy::new(Cursor::new(vec![])));
+        for item in [&b"abcdefghij"[..], b"", b"12"] {
+            poll_until_ready!(encoder.feed(item))?;
+        }
+        poll_until_ready!(SinkExt::<&[u8]>::flush(&mut encoder))?;
+        let mut cursor = encoder.into_inner().inner;
+        assert_eq!(
+            cursor.get_ref(),
+            &[
+                0, 0, 0, 0, 0, 0, 0, 10, //
+                97, 98, 99, 100, 101, 102, 103, 104, 105, 106, //
+                0, 0, 0, 0, 0, 0, 0, 0, //
+                0, 0, 0, 0, 0, 0, 0, 2, //
+                49, 50, //
+            ]
+        );
+
+        cursor.set_position(0);
+        let mut decoder = Decoder::new(Choppy::new(cursor));
+        let mut frames = vec![];
+        while let Some(frame) = poll_until_ready!(decoder.next()) {
+            frames.push(frame?);
+        }
+        assert_eq!(frames, vec![b"abcdefghij".to_vec(), vec![], b"12".to_vec()]);
+
+        Ok(())
+    }
 }
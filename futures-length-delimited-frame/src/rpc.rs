@@ -0,0 +1,421 @@
+//! Request/response over length-delimited frames.
+//!
+//! Every frame payload starts with an 8 byte big-endian request id, followed by the body.
+//! The server echoes the id of the request in its response.
+
+use core::{
+    future::Future,
+    sync::atomic::{AtomicU64, Ordering},
+};
+use std::{
+    collections::HashMap,
+    io::{Error as IoError, ErrorKind as IoErrorKind},
+    sync::{Arc, Mutex},
+};
+
+use futures_channel::{mpsc, oneshot};
+use futures_io::{AsyncRead, AsyncWrite};
+use futures_util::{
+    future::{self, Either},
+    pin_mut, SinkExt, StreamExt as _,
+};
+
+use crate::{Decoder, Encoder};
+
+//
+const ID_LEN: usize = core::mem::size_of::<u64>();
+
+type Pending = Arc<Mutex<HashMap<u64, oneshot::Sender<Vec<u8>>>>>;
+
+//
+//
+//
+#[derive(Debug, Clone)]
+pub struct Client {
+    next_id: Arc<AtomicU64>,
+    pending: Pending,
+    tx: mpsc::UnboundedSender<Vec<u8>>,
+}
+
+/// Create a [`Client`] and the driver future that must be polled (e.g. spawned) for calls to make progress.
+///
+/// The driver routes incoming frames to waiting calls and resolves once every [`Client`] is dropped
+/// or the peer closes the connection.
+pub fn client<R, W>(
+    decoder: Decoder<R>,
+    encoder: Encoder<W>,
+) -> (Client, impl Future<Output = Result<(), IoError>>)
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let (tx, rx) = mpsc::unbounded();
+    let pending: Pending = Default::default();
+
+    let client = Client {
+        next_id: Arc::new(AtomicU64::new(0)),
+        pending: pending.clone(),
+        tx,
+    };
+
+    (client, drive(decoder, encoder, rx, pending))
+}
+
+impl Client {
+    pub async fn call(&self, req: impl AsRef<[u8]>) -> Result<Vec<u8>, IoError> {
+        self.call_with_timeout(req, future::pending::<()>()).await
+    }
+
+    /// Like [`Client::call`], but fails with [`IoErrorKind::TimedOut`] once `timeout` resolves,
+    /// e.g. `tokio::time::sleep(dur)` or `async_io::Timer::after(dur)`.
+    pub async fn call_with_timeout<F: Future>(
+        &self,
+        req: impl AsRef<[u8]>,
+        timeout: F,
+    ) -> Result<Vec<u8>, IoError> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+
+        let (res_tx, res_rx) = oneshot::channel();
+        self.pending
+            .lock()
+            .expect("Never poisoned")
+            .insert(id, res_tx);
+        // Also when this future is dropped before it resolves.
+        let _guard = PendingGuard {
+            pending: &self.pending,
+            id,
+        };
+
+        if self
+            .tx
+            .unbounded_send(encode_frame(id, req.as_ref()))
+            .is_err()
+        {
+            return Err(closed());
+        }
+
+        pin_mut!(timeout);
+        match future::select(res_rx, timeout).await {
+            Either::Left((Ok(res), _)) => Ok(res),
+            Either::Left((Err(_), _)) => Err(closed()),
+            Either::Right(_) => Err(IoError::new(
+                IoErrorKind::TimedOut,
+                format!("call timed out, id:{id}"),
+            )),
+        }
+    }
+}
+
+struct PendingGuard<'a> {
+    pending: &'a Pending,
+    id: u64,
+}
+
+impl Drop for PendingGuard<'_> {
+    fn drop(&mut self) {
+        self.pending
+            .lock()
+            .expect("Never poisoned")
+            .remove(&self.id);
+    }
+}
+
+async fn drive<R, W>(
+    mut decoder: Decoder<R>,
+    mut encoder: Encoder<W>,
+    mut rx: mpsc::UnboundedReceiver<Vec<u8>>,
+    pending: Pending,
+) -> Result<(), IoError>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    // Reading and writing run concurrently, a large request being written never stops
+    // the responses from being read.
+    let ret = async {
+        let rx_done = {
+            let read = async {
+                while let Some(frame) = decoder.next().await {
+                    route(&pending, frame?)?;
+                }
+                Result::<_, IoError>::Ok(())
+            };
+            let write = async {
+                while let Some(frame) = rx.next().await {
+                    encoder.send(frame).await?;
+                }
+                Result::<_, IoError>::Ok(())
+            };
+            pin_mut!(read, write);
+
+            match future::select(read, write).await {
+                // The peer closed the connection.
+                Either::Left((ret, _)) => ret.map(|_| false)?,
+                Either::Right((ret, _)) => ret.map(|_| true)?,
+            }
+        };
+        if !rx_done {
+            return Ok(());
+        }
+
+        // Every Client is dropped, wait for the responses of the remaining calls.
+        loop {
+            if pending.lock().expect("Never poisoned").is_empty() {
+                break;
+            }
+            match decoder.next().await {
+                Some(frame) => route(&pending, frame?)?,
+                None => return Ok(()),
+            }
+        }
+
+        SinkExt::<Vec<u8>>::close(&mut encoder).await
+    }
+    .await;
+
+    // Wake up the remaining calls, they fail as the connection is gone.
+    // Closing first, a call started after the clear fails to send instead of waiting forever.
+    rx.close();
+    pending.lock().expect("Never poisoned").clear();
+
+    ret
+}
+
+fn route(pending: &Pending, frame: Vec<u8>) -> Result<(), IoError> {
+    let (id, res) = decode_frame(frame)?;
+
+    // The call may already have timed out.
+    if let Some(res_tx) = pending.lock().expect("Never poisoned").remove(&id) {
+        let _ = res_tx.send(res);
+    }
+
+    Ok(())
+}
+
+//
+//
+//
+/// Answer every request read from `decoder` with `handler`, running at most `concurrency` handlers at once.
+///
+/// Responses are written in completion order and resolves once the peer closes the connection.
+///
+/// # Panics
+///
+/// Panics if `concurrency` is 0.
+pub async fn serve<R, W, H, Fut>(
+    decoder: Decoder<R>,
+    encoder: Encoder<W>,
+    handler: H,
+    concurrency: usize,
+) -> Result<(), IoError>
+where
+    R: AsyncRead,
+    W: AsyncWrite,
+    H: Fn(Vec<u8>) -> Fut,
+    Fut: Future<Output = Vec<u8>>,
+{
+    assert!(concurrency > 0, "concurrency must be greater than 0");
+
+    let handler = &handler;
+
+    // Responses go through a channel, so requests are still read while a response is written.
+    let (tx, rx) = mpsc::channel::<Vec<u8>>(concurrency);
+
+    let read = decoder
+        .map(|frame| async move {
+            let (id, req) = decode_frame(frame?)?;
+            let res = handler(req).await;
+            Ok(encode_frame(id, &res))
+        })
+        .buffer_unordered(concurrency)
+        .forward(tx.sink_map_err(|_| closed()));
+    let write = rx.map(Ok).forward(encoder);
+
+    future::try_join(read, write).await.map(|_| ())
+}
+
+//
+//
+//
+fn encode_frame(id: u64, body: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(ID_LEN + body.len());
+    frame.extend_from_slice(id.to_be_bytes().as_ref());
+    frame.extend_from_slice(body);
+    frame
+}
+
+fn decode_frame(mut frame: Vec<u8>) -> Result<(u64, Vec<u8>), IoError> {
+    if frame.len() < ID_LEN {
+        return Err(IoError::new(
+            IoErrorKind::InvalidData,
+            format!("need more id, n:{}", ID_LEN - frame.len()),
+        ));
+    }
+
+    let id = u64::from_be_bytes(frame[..ID_LEN].try_into().expect("Never"));
+    frame.drain(..ID_LEN);
+
+    Ok((id, frame))
+}
+
+fn closed() -> IoError {
+    IoError::new(IoErrorKind::BrokenPipe, "connection closed")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    use std::{os::unix::net::UnixStream, time::Duration};
+
+    #[cfg(unix)]
+    use async_io::{Async, Timer};
+    #[cfg(unix)]
+    use futures_util::{
+        future::{join, join_all},
+        AsyncReadExt as _, FutureExt as _,
+    };
+
+    #[cfg(unix)]
+    #[test]
+    fn simple() -> Result<(), Box<dyn std::error::Error>> {
+        futures_executor::block_on(async {
+            let (client_stream, server_stream) = Async::<UnixStream>::pair()?;
+
+            let (r, w) = client_stream.split();
+            let (client, driver) = client(Decoder::new(r), Encoder::new(w));
+
+            let (r, w) = server_stream.split();
+            let server = serve(
+                Decoder::new(r),
+                Encoder::new(w),
+                |req: Vec<u8>| async move {
+                    if req == b"sleep" {
+                        Timer::after(Duration::from_millis(200)).await;
+                    }
+                    req.into_iter().rev().collect()
+                },
+                2,
+            );
+
+            let calls = async move {
+                assert_eq!(client.call(b"abc").await?, b"cba");
+                assert_eq!(client.call(b"").await?, b"");
+
+                let (a, b) = join(client.call(b"sleep"), client.call(b"12")).await;
+                assert_eq!(a?, b"peels");
+                assert_eq!(b?, b"21");
+
+                let err = client
+                    .call_with_timeout(b"sleep", Timer::after(Duration::from_millis(50)))
+                    .await
+                    .err()
+                    .ok_or("call_with_timeout should time out")?;
+                assert_eq!(err.kind(), IoErrorKind::TimedOut);
+
+                // A call dropped before it resolves leaves no pending entry.
+                assert!(client.call(b"sleep").now_or_never().is_none());
+                assert!(client.pending.lock().expect("Never poisoned").is_empty());
+
+                // The late response of the timed out call is dropped.
+                Timer::after(Duration::from_millis(300)).await;
+                assert_eq!(client.call(b"xyz").await?, b"zyx");
+
+                Result::<_, Box<dyn std::error::Error>>::Ok(())
+            };
+
+            let ((driver_ret, calls_ret), server_ret) = join(join(driver, calls), server).await;
+            calls_ret?;
+            driver_ret?;
+            server_ret?;
+
+            Ok(())
+        })
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_large_concurrent_calls() -> Result<(), Box<dyn std::error::Error>> {
+        futures_executor::block_on(async {
+            let (client_stream, server_stream) = Async::<UnixStream>::pair()?;
+
+            let (r, w) = client_stream.split();
+            let (client, driver) = client(Decoder::new(r), Encoder::new(w));
+
+            let (r, w) = server_stream.split();
+            let server = serve(Decoder::new(r), Encoder::new(w), |req| async { req }, 4);
+
+            let calls = async move {
+                // Every frame is larger than the socket buffers (about 200 KiB on Linux).
+                let reqs = (0..4_u8).map(|i| vec![i; 1024 * 1024]).collect::<Vec<_>>();
+                let rets = join_all(reqs.iter().map(|req| client.call(req))).await;
+                for (req, ret) in reqs.iter().zip(rets) {
+                    assert_eq!(&ret?, req);
+                }
+                Result::<_, Box<dyn std::error::Error>>::Ok(())
+            };
+
+            let timeout = Timer::after(Duration::from_secs(30));
+            let all = join(join(driver, calls), server);
+            pin_mut!(all);
+            match future::select(all, timeout).await {
+                Either::Left((((driver_ret, calls_ret), server_ret), _)) => {
+                    calls_ret?;
+                    driver_ret?;
+                    server_ret?;
+                }
+                Either::Right(_) => panic!("calls hang"),
+            }
+
+            Ok(())
+        })
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_send() -> Result<(), Box<dyn std::error::Error>> {
+        fn assert_send<T: Send>(_: &T) {}
+
+        let (client_stream, server_stream) = Async::<UnixStream>::pair()?;
+
+        let (r, w) = client_stream.split();
+        let (client, driver) = client(Decoder::new(r), Encoder::new(w));
+        assert_send(&driver);
+        assert_send(&client.call(b"abc"));
+
+        let (r, w) = server_stream.split();
+        let server = serve(Decoder::new(r), Encoder::new(w), |req| async { req }, 1);
+        assert_send(&server);
+
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "concurrency must be greater than 0")]
+    fn test_serve_zero_concurrency() {
+        let _ = futures_executor::block_on(serve(
+            Decoder::new(&b""[..]),
+            Encoder::new(Vec::new()),
+            |req| async { req },
+            0,
+        ));
+    }
+
+    #[test]
+    fn test_frame() -> Result<(), Box<dyn std::error::Error>> {
+        let frame = encode_frame(3, b"abc");
+        assert_eq!(frame, &[0, 0, 0, 0, 0, 0, 0, 3, 97, 98, 99]);
+        assert_eq!(decode_frame(frame)?, (3, b"abc".to_vec()));
+
+        match decode_frame(vec![0, 0, 0]) {
+            Err(err) => {
+                assert_eq!(err.kind(), IoErrorKind::InvalidData);
+                assert!(err.to_string().contains("need more id, n:5"));
+            }
+            x => panic!("{x:?}"),
+        }
+
+        Ok(())
+    }
+}
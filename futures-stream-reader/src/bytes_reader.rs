@@ -0,0 +1,507 @@
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::{
+    io::{Error as IoError, IoSliceMut},
+    sync::{Arc, Mutex},
+};
+
+use bytes::{Bytes, BytesMut};
+use futures_util::{ready, stream::FusedStream, AsyncRead, Stream};
+use pin_project_lite::pin_project;
+
+use crate::{
+    on_error::OnError,
+    read_state::{ReadRet, ReadState},
+    read_strategy::ReadStrategy,
+    DEFAULT_CAPACITY,
+};
+
+//
+pub fn bytes_reader<R: AsyncRead>(reader: R) -> BytesReaderStream<R> {
+    bytes_reader_with_capacity(reader, DEFAULT_CAPACITY)
+}
+
+pub fn bytes_reader_with_capacity<R: AsyncRead>(
+    reader: R,
+    capacity: usize,
+) -> BytesReaderStream<R> {
+    BytesReaderStream {
+        reader,
+        buf: BytesMut::with_capacity(capacity),
+        capacity,
+        state: ReadState::new(ReadStrategy::Exact(capacity)),
+    }
+}
+
+pin_project! {
+    /// Like [`crate::ReaderStream`], but every chunk is split off one shared [`BytesMut`],
+    /// which only reserves `capacity` more bytes once it is used up.
+    #[derive(Debug)]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct BytesReaderStream<R> {
+        #[pin]
+        reader: R,
+        buf: BytesMut,
+        capacity: usize,
+        state: ReadState,
+    }
+}
+
+impl<R> BytesReaderStream<R> {
+    pub fn set_on_error(&mut self, on_error: OnError) {
+        self.state.set_on_error(on_error);
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut R> {
+        self.project().reader
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: AsyncRead> FusedStream for BytesReaderStream<R> {
+    fn is_terminated(&self) -> bool {
+        self.state.is_done()
+    }
+}
+
+impl<R: AsyncRead> Stream for BytesReaderStream<R> {
+    type Item = Result<Bytes, IoError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        loop {
+            let Some(len) = this.state.begin() else {
+                return Poll::Ready(None);
+            };
+
+            if this.buf.capacity() == 0 {
+                this.buf.reserve(*this.capacity);
+            }
+            // futures_io::AsyncRead needs initialized memory.
+            let len = len.min(this.buf.capacity());
+            this.buf.resize(len, 0);
+
+            let ret = ready!(this.reader.as_mut().poll_read(cx, &mut this.buf[..]));
+            return match this.state.finish(ret) {
+                ReadRet::Chunk(n) => {
+                    this.buf.truncate(n);
+                    Poll::Ready(Some(Ok(this.buf.split().freeze())))
+                }
+                ReadRet::Eof => Poll::Ready(None),
+                ReadRet::Err(err) => Poll::Ready(Some(Err(err))),
+                ReadRet::Retry => continue,
+                ReadRet::RetryLater => {
+                    cx.waker().wake_by_ref();
+                    Poll::Pending
+                }
+            };
+        }
+    }
+}
+
+//
+//
+//
+/// Buffers handed out by [`PooledReaderStream`], give them back with [`BufPool::put`].
+#[derive(Debug, Clone, Default)]
+pub struct BufPool {
+    bufs: Arc<Mutex<Vec<BytesMut>>>,
+}
+
+impl BufPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, capacity: usize) -> BytesMut {
+        let mut buf = self
+            .bufs
+            .lock()
+            .expect("Never poisoned")
+            .pop()
+            .unwrap_or_default();
+        buf.clear();
+        buf.reserve(capacity);
+        buf
+    }
+
+    pub fn put(&self, buf: BytesMut) {
+        self.bufs.lock().expect("Never poisoned").push(buf);
+    }
+
+    pub fn len(&self) -> usize {
+        self.bufs.lock().expect("Never poisoned").len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Zero-filled, futures_io::AsyncRead needs initialized memory.
+    fn get_filled(&self, capacity: usize) -> BytesMut {
+        let mut buf = self.get(capacity);
+        buf.resize(capacity, 0);
+        buf
+    }
+}
+
+pub fn pooled_reader<R: AsyncRead>(reader: R, pool: BufPool) -> PooledReaderStream<R> {
+    pooled_reader_with_capacity(reader, DEFAULT_CAPACITY, pool)
+}
+
+pub fn pooled_reader_with_capacity<R: AsyncRead>(
+    reader: R,
+    capacity: usize,
+    pool: BufPool,
+) -> PooledReaderStream<R> {
+    PooledReaderStream {
+        reader,
+        pool,
+        capacity,
+        buf: None,
+        state: ReadState::new(ReadStrategy::Exact(capacity)),
+    }
+}
+
+pin_project! {
+    /// Every chunk is read into a buffer taken from `pool`, or a new one if the pool is empty.
+    ///
+    /// A buffer that was not yielded is kept for the next read, and put back once the stream ends.
+    #[derive(Debug)]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct PooledReaderStream<R> {
+        #[pin]
+        reader: R,
+        pool: BufPool,
+        capacity: usize,
+        buf: Option<BytesMut>,
+        state: ReadState,
+    }
+}
+
+impl<R> PooledReaderStream<R> {
+    pub fn set_on_error(&mut self, on_error: OnError) {
+        self.state.set_on_error(on_error);
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut R> {
+        self.project().reader
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: AsyncRead> FusedStream for PooledReaderStream<R> {
+    fn is_terminated(&self) -> bool {
+        self.state.is_done()
+    }
+}
+
+impl<R: AsyncRead> Stream for PooledReaderStream<R> {
+    type Item = Result<BytesMut, IoError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        let ret = loop {
+            let Some(len) = this.state.begin() else {
+                return Poll::Ready(None);
+            };
+
+            let capacity = *this.capacity;
+            let pool = &*this.pool;
+            let buf = this.buf.get_or_insert_with(|| pool.get_filled(capacity));
+
+            let ret = ready!(this.reader.as_mut().poll_read(cx, &mut buf[..len]));
+            break match this.state.finish(ret) {
+                ReadRet::Chunk(n) => {
+                    let mut buf = this.buf.take().expect("Never");
+                    buf.truncate(n);
+                    return Poll::Ready(Some(Ok(buf)));
+                }
+                ReadRet::Eof => Poll::Ready(None),
+                ReadRet::Err(err) => Poll::Ready(Some(Err(err))),
+                ReadRet::Retry => continue,
+                ReadRet::RetryLater => {
+                    cx.waker().wake_by_ref();
+                    return Poll::Pending;
+                }
+            };
+        };
+
+        if this.state.is_done() {
+            if let Some(buf) = this.buf.take() {
+                this.pool.put(buf);
+            }
+        }
+
+        ret
+    }
+}
+
+pub fn pooled_vectored_reader<R: AsyncRead>(
+    reader: R,
+    n_bufs: usize,
+    pool: BufPool,
+) -> PooledVectoredReaderStream<R> {
+    pooled_vectored_reader_with_capacity(reader, DEFAULT_CAPACITY, n_bufs, pool)
+}
+
+pub fn pooled_vectored_reader_with_capacity<R: AsyncRead>(
+    reader: R,
+    capacity: usize,
+    n_bufs: usize,
+    pool: BufPool,
+) -> PooledVectoredReaderStream<R> {
+    PooledVectoredReaderStream {
+        reader,
+        pool,
+        capacity,
+        n_bufs: n_bufs.max(1),
+        bufs: vec![],
+        state: ReadState::new(ReadStrategy::Exact(capacity)),
+    }
+}
+
+pin_project! {
+    /// Like [`PooledReaderStream`], but every read is a vectored read into `n_bufs` buffers,
+    /// the filled ones are yielded as a batch.
+    ///
+    /// Only the yielded buffers are replaced from the pool, the others are kept for the next read.
+    #[derive(Debug)]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct PooledVectoredReaderStream<R> {
+        #[pin]
+        reader: R,
+        pool: BufPool,
+        capacity: usize,
+        n_bufs: usize,
+        // Every buffer is filled up to `capacity`.
+        bufs: Vec<BytesMut>,
+        state: ReadState,
+    }
+}
+
+impl<R> PooledVectoredReaderStream<R> {
+    pub fn set_on_error(&mut self, on_error: OnError) {
+        self.state.set_on_error(on_error);
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut R> {
+        self.project().reader
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: AsyncRead> FusedStream for PooledVectoredReaderStream<R> {
+    fn is_terminated(&self) -> bool {
+        self.state.is_done()
+    }
+}
+
+impl<R: AsyncRead> Stream for PooledVectoredReaderStream<R> {
+    type Item = Result<Vec<BytesMut>, IoError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        let ret = loop {
+            if this.state.begin().is_none() {
+                return Poll::Ready(None);
+            }
+
+            let capacity = *this.capacity;
+            while this.bufs.len() < *this.n_bufs {
+                this.bufs.push(this.pool.get_filled(capacity));
+            }
+
+            let ret = {
+                let mut slices = this
+                    .bufs
+                    .iter_mut()
+                    .map(|x| IoSliceMut::new(x))
+                    .collect::<Vec<_>>();
+                ready!(this.reader.as_mut().poll_read_vectored(cx, &mut slices))
+            };
+            break match this.state.finish(ret) {
+                ReadRet::Chunk(n) => {
+                    let n_filled = (n + capacity - 1) / capacity;
+                    let mut batch = this.bufs.drain(..n_filled).collect::<Vec<_>>();
+                    if let Some(last) = batch.last_mut() {
+                        last.truncate(n - (n_filled - 1) * capacity);
+                    }
+                    return Poll::Ready(Some(Ok(batch)));
+                }
+                ReadRet::Eof => Poll::Ready(None),
+                ReadRet::Err(err) => Poll::Ready(Some(Err(err))),
+                ReadRet::Retry => continue,
+                ReadRet::RetryLater => {
+                    cx.waker().wake_by_ref();
+                    return Poll::Pending;
+                }
+            };
+        };
+
+        if this.state.is_done() {
+            this.bufs.drain(..).for_each(|x| this.pool.put(x));
+        }
+
+        ret
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::ErrorKind as IoErrorKind;
+
+    use futures_util::{io::Cursor, StreamExt as _};
+
+    // Every read fails.
+    struct Failing;
+
+    impl AsyncRead for Failing {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            _buf: &mut [u8],
+        ) -> Poll<Result<usize, IoError>> {
+            Poll::Ready(Err(IoErrorKind::Other.into()))
+        }
+    }
+
+    #[test]
+    fn test_bytes_reader() -> Result<(), Box<dyn std::error::Error>> {
+        futures_executor::block_on(async {
+            let r = Cursor::new(b"1234567890");
+
+            let st = bytes_reader_with_capacity(r, 3);
+            let chunks = st
+                .collect::<Vec<_>>()
+                .await
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(chunks.concat(), b"1234567890");
+            assert!(chunks.iter().all(|x| !x.is_empty() && x.len() <= 3));
+
+            // Ends after the first error by default.
+            let mut st = bytes_reader(Failing);
+            let err = st.next().await.ok_or("st.next() is_none")?.err();
+            assert_eq!(err.map(|x| x.kind()), Some(IoErrorKind::Other));
+            assert!(st.next().await.is_none());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_pooled_reader() -> Result<(), Box<dyn std::error::Error>> {
+        futures_executor::block_on(async {
+            let r = Cursor::new(b"1234567890");
+            let pool = BufPool::new();
+
+            let mut st = pooled_reader_with_capacity(r, 3, pool.clone());
+            let mut n = 0;
+            while let Some(ret) = st.next().await {
+                let buf = ret?;
+                n += 1;
+                match n {
+                    1 => assert_eq!(&buf[..], b"123"),
+                    2 => assert_eq!(&buf[..], b"456"),
+                    3 => assert_eq!(&buf[..], b"789"),
+                    4 => assert_eq!(&buf[..], b"0"),
+                    _ => unreachable!(),
+                }
+                pool.put(buf);
+                assert_eq!(pool.len(), 1);
+            }
+            assert_eq!(n, 4);
+            assert_eq!(pool.len(), 1);
+
+            // Ends after the first error by default, the buffer goes back to the pool.
+            let mut st = pooled_reader(Failing, pool.clone());
+            let err = st.next().await.ok_or("st.next() is_none")?.err();
+            assert_eq!(err.map(|x| x.kind()), Some(IoErrorKind::Other));
+            assert!(st.next().await.is_none());
+            assert_eq!(pool.len(), 1);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_pooled_vectored_reader() -> Result<(), Box<dyn std::error::Error>> {
+        futures_executor::block_on(async {
+            let r = Cursor::new(b"1234567890");
+            let pool = BufPool::new();
+
+            let mut st = pooled_vectored_reader_with_capacity(r, 3, 2, pool.clone());
+
+            let batch = st.next().await.ok_or("st.next() is_none")??;
+            assert_eq!(batch, [&b"123"[..], b"456"]);
+            batch.into_iter().for_each(|x| pool.put(x));
+
+            let batch = st.next().await.ok_or("st.next() is_none")??;
+            assert_eq!(batch, [&b"789"[..], b"0"]);
+            batch.into_iter().for_each(|x| pool.put(x));
+
+            assert!(st.next().await.is_none());
+            assert_eq!(pool.len(), 2);
+
+            // A partly filled batch keeps the unused buffer for the next read.
+            let r = Cursor::new(b"12345");
+            let mut st = pooled_vectored_reader_with_capacity(r, 4, 3, pool.clone());
+            let batch = st.next().await.ok_or("st.next() is_none")??;
+            assert_eq!(batch, [&b"1234"[..], b"5"]);
+            assert!(pool.is_empty());
+            // Refilled to 3 buffers for the read that hit EOF.
+            assert!(st.next().await.is_none());
+            assert_eq!(pool.len(), 3);
+
+            // Ends after the first error by default, the buffers go back to the pool.
+            let mut st = pooled_vectored_reader(Failing, 2, pool.clone());
+            let err = st.next().await.ok_or("st.next() is_none")?.err();
+            assert_eq!(err.map(|x| x.kind()), Some(IoErrorKind::Other));
+            assert!(st.next().await.is_none());
+            assert_eq!(pool.len(), 3);
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,354 @@
+use core::{
+    fmt,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::{error, io::Error as IoError};
+
+use futures_util::{
+    ready,
+    stream::{self, FusedStream, Iter},
+    AsyncRead, Stream,
+};
+use pin_project_lite::pin_project;
+
+use crate::{
+    on_error::OnError,
+    read_state::{ReadRet, ReadState},
+    read_strategy::ReadStrategy,
+    DEFAULT_CAPACITY,
+};
+
+//
+type ChainIter<I, R> = Iter<core::iter::Map<I, fn(R) -> Result<R, IoError>>>;
+
+/// Stream of the byte chunks of every reader of `readers` in order.
+pub fn chain<I, R>(readers: I) -> ChainStream<ChainIter<I::IntoIter, R>, R>
+where
+    I: IntoIterator<Item = R>,
+    R: AsyncRead,
+{
+    ChainStream::new(stream::iter(
+        readers.into_iter().map(Ok as fn(R) -> Result<R, IoError>),
+    ))
+}
+
+//
+pin_project! {
+    /// Stream of the byte chunks of every reader yielded by `sources` in order.
+    ///
+    /// A reader is only pulled from `sources` once the previous one is at EOF, so it can be opened lazily,
+    /// e.g. `stream::iter(paths).then(open)`. An error of `sources` ends the stream, a read error is handled
+    /// as set by [`ChainStream::set_on_error`]. Every error yielded wraps a [`ChainError`].
+    #[must_use = "streams do nothing unless polled"]
+    pub struct ChainStream<S, R> {
+        #[pin]
+        sources: S,
+        #[pin]
+        current: Option<R>,
+        index: usize,
+        buf: Vec<u8>,
+        state: ReadState,
+    }
+}
+
+impl<S, R> ChainStream<S, R>
+where
+    S: Stream<Item = Result<R, IoError>>,
+    R: AsyncRead,
+{
+    pub fn new(sources: S) -> Self {
+        Self::with_capacity(sources, DEFAULT_CAPACITY)
+    }
+
+    pub fn with_capacity(sources: S, capacity: usize) -> Self {
+        Self {
+            sources,
+            current: None,
+            index: 0,
+            buf: vec![0; capacity],
+            state: ReadState::new(ReadStrategy::Exact(capacity)),
+        }
+    }
+}
+
+impl<S, R> ChainStream<S, R> {
+    /// By default a read error ends the stream.
+    pub fn set_on_error(&mut self, on_error: OnError) {
+        self.state.set_on_error(on_error);
+    }
+
+    /// The index of the current source, starting at 0.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.sources
+    }
+
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.sources
+    }
+
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut S> {
+        self.project().sources
+    }
+
+    pub fn into_inner(self) -> S {
+        self.sources
+    }
+}
+
+impl<S: fmt::Debug, R: fmt::Debug> fmt::Debug for ChainStream<S, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChainStream")
+            .field("sources", &self.sources)
+            .field("current", &self.current)
+            .field("index", &self.index)
+            .finish()
+    }
+}
+
+//
+impl<S, R> FusedStream for ChainStream<S, R>
+where
+    S: Stream<Item = Result<R, IoError>>,
+    R: AsyncRead,
+{
+    fn is_terminated(&self) -> bool {
+        self.state.is_done()
+    }
+}
+
+//
+impl<S, R> Stream for ChainStream<S, R>
+where
+    S: Stream<Item = Result<R, IoError>>,
+    R: AsyncRead,
+{
+    type Item = Result<Vec<u8>, IoError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        loop {
+            let Some(len) = this.state.begin() else {
+                return Poll::Ready(None);
+            };
+
+            let reader = match this.current.as_mut().as_pin_mut() {
+                Some(reader) => reader,
+                None => {
+                    match ready!(this.sources.as_mut().poll_next(cx)) {
+                        Some(Ok(reader)) => this.current.set(Some(reader)),
+                        Some(Err(err)) => {
+                            this.state.terminate();
+                            return Poll::Ready(Some(Err(ChainError::wrap(*this.index, err))));
+                        }
+                        None => {
+                            this.state.terminate();
+                            return Poll::Ready(None);
+                        }
+                    }
+                    continue;
+                }
+            };
+
+            let ret = match ready!(reader.poll_read(cx, &mut this.buf[..len])) {
+                // The next source, not the end of the stream.
+                Ok(0) => {
+                    this.current.set(None);
+                    *this.index += 1;
+                    continue;
+                }
+                ret => ret,
+            };
+            match this.state.finish(ret) {
+                ReadRet::Chunk(n) => return Poll::Ready(Some(Ok(this.buf[..n].to_vec()))),
+                // Ok(0) is handled above.
+                ReadRet::Eof => unreachable!(),
+                ReadRet::Err(err) => {
+                    return Poll::Ready(Some(Err(ChainError::wrap(*this.index, err))))
+                }
+                ReadRet::Retry => continue,
+                ReadRet::RetryLater => {
+                    cx.waker().wake_by_ref();
+                    return Poll::Pending;
+                }
+            }
+        }
+    }
+}
+
+//
+//
+//
+/// The error of a source of a [`ChainStream`], get it with [`IoError::get_ref`] and `downcast_ref`.
+#[derive(Debug)]
+pub struct ChainError {
+    index: usize,
+    err: IoError,
+}
+
+impl ChainError {
+    fn wrap(index: usize, err: IoError) -> IoError {
+        IoError::new(err.kind(), Self { index, err })
+    }
+
+    /// The index of the failed source.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn inner(&self) -> &IoError {
+        &self.err
+    }
+}
+
+impl fmt::Display for ChainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "source failed, index:{}, err:{}", self.index, self.err)
+    }
+}
+
+impl error::Error for ChainError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(&self.err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::ErrorKind as IoErrorKind;
+
+    use futures_util::{io::Cursor, StreamExt as _};
+
+    use crate::on_error::ErrorAction;
+
+    // Fails once with `kind`, then reads from the inner reader.
+    struct FailOnce<R> {
+        inner: R,
+        kind: Option<IoErrorKind>,
+    }
+
+    impl<R: AsyncRead + Unpin> AsyncRead for FailOnce<R> {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<Result<usize, IoError>> {
+            if let Some(kind) = self.kind.take() {
+                return Poll::Ready(Err(kind.into()));
+            }
+            Pin::new(&mut self.inner).poll_read(cx, buf)
+        }
+    }
+
+    #[test]
+    fn simple() -> Result<(), Box<dyn std::error::Error>> {
+        futures_executor::block_on(async {
+            let readers = vec![
+                Cursor::new(b"head".to_vec()),
+                Cursor::new(vec![]),
+                Cursor::new(b"12345".to_vec()),
+            ];
+            let st = ChainStream::with_capacity(stream::iter(readers).map(Ok), 3);
+            let chunks = st.collect::<Vec<_>>().await;
+            let chunks = chunks.into_iter().collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(chunks, [&b"hea"[..], b"d", b"123", b"45"]);
+
+            let chunks = chain([Cursor::new(b"1"), Cursor::new(b"2")])
+                .collect::<Vec<_>>()
+                .await;
+            assert_eq!(chunks.len(), 2);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_error() -> Result<(), Box<dyn std::error::Error>> {
+        futures_executor::block_on(async {
+            let sources = stream::iter(0..3).map(|i| match i {
+                1 => Err(IoError::new(IoErrorKind::NotFound, "file not found")),
+                _ => Ok(Cursor::new(b"12")),
+            });
+            let mut st = ChainStream::new(sources);
+
+            assert_eq!(st.next().await.ok_or("st.next() is_none")??, b"12");
+            let err = st
+                .next()
+                .await
+                .ok_or("st.next() is_none")?
+                .err()
+                .ok_or("should fail")?;
+            assert_eq!(err.kind(), IoErrorKind::NotFound);
+            assert_eq!(
+                err.to_string(),
+                "source failed, index:1, err:file not found"
+            );
+            let chain_err = err
+                .get_ref()
+                .and_then(|x| x.downcast_ref::<ChainError>())
+                .ok_or("should be a ChainError")?;
+            assert_eq!(chain_err.index(), 1);
+            assert!(st.next().await.is_none());
+            assert!(st.is_terminated());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_on_error() -> Result<(), Box<dyn std::error::Error>> {
+        futures_executor::block_on(async {
+            let new = || {
+                chain([
+                    FailOnce {
+                        inner: Cursor::new(b"12"),
+                        kind: Some(IoErrorKind::Interrupted),
+                    },
+                    FailOnce {
+                        inner: Cursor::new(b"34"),
+                        kind: Some(IoErrorKind::Other),
+                    },
+                ])
+            };
+            let kinds = |rets: Vec<Result<Vec<u8>, IoError>>| {
+                rets.into_iter()
+                    .map(|x| x.map_err(|err| err.kind()))
+                    .collect::<Vec<_>>()
+            };
+
+            // Ends after the first error by default.
+            let st = new();
+            assert_eq!(
+                kinds(st.collect().await),
+                vec![Err(IoErrorKind::Interrupted)]
+            );
+
+            // Interrupted is retried, the other error is yielded and reading goes on.
+            let mut st = new();
+            st.set_on_error(OnError::classify(|err| match err.kind() {
+                IoErrorKind::Interrupted => ErrorAction::Retry,
+                _ => ErrorAction::Yield,
+            }));
+            let rets = st.collect::<Vec<_>>().await;
+            let err = rets[1].as_ref().err().ok_or("should fail")?;
+            assert_eq!(err.to_string(), "source failed, index:1, err:other error");
+            assert_eq!(
+                kinds(rets),
+                vec![
+                    Ok(b"12".to_vec()),
+                    Err(IoErrorKind::Other),
+                    Ok(b"34".to_vec())
+                ]
+            );
+
+            Ok(())
+        })
+    }
+}
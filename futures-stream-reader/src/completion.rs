@@ -0,0 +1,182 @@
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::io::{Error as IoError, ErrorKind as IoErrorKind};
+
+use futures_channel::oneshot;
+use futures_util::{ready, stream::FusedStream, AsyncRead, Stream};
+
+use crate::ReaderStream;
+
+//
+/// Why a [`CompletionStream`] finished.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamEnd {
+    Eof,
+    /// The error ended the stream, it was yielded as the last item.
+    Error(IoErrorKind),
+    /// The stream was dropped before its end.
+    Dropped,
+}
+
+/// Sent once a [`CompletionStream`] finished.
+#[derive(Debug)]
+pub struct Completed<R> {
+    pub bytes_read: u64,
+    pub end: StreamEnd,
+    pub reader: R,
+}
+
+//
+impl<R: AsyncRead + Unpin> ReaderStream<R> {
+    /// The receiver resolves once the stream finished, with the reader back.
+    pub fn with_completion(self) -> (CompletionStream<R>, oneshot::Receiver<Completed<R>>) {
+        let (tx, rx) = oneshot::channel();
+        let st = CompletionStream {
+            inner: Some(self),
+            bytes_read: 0,
+            tx: Some(tx),
+        };
+        (st, rx)
+    }
+}
+
+//
+/// See [`ReaderStream::with_completion`].
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct CompletionStream<R: AsyncRead + Unpin> {
+    inner: Option<ReaderStream<R>>,
+    bytes_read: u64,
+    tx: Option<oneshot::Sender<Completed<R>>>,
+}
+
+impl<R: AsyncRead + Unpin> CompletionStream<R> {
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes_read
+    }
+
+    fn complete(&mut self, end: StreamEnd) {
+        if let (Some(inner), Some(tx)) = (self.inner.take(), self.tx.take()) {
+            let _ = tx.send(Completed {
+                bytes_read: self.bytes_read,
+                end,
+                reader: inner.into_inner(),
+            });
+        }
+    }
+}
+
+impl<R: AsyncRead + Unpin> Drop for CompletionStream<R> {
+    fn drop(&mut self) {
+        self.complete(StreamEnd::Dropped);
+    }
+}
+
+//
+impl<R: AsyncRead + Unpin> FusedStream for CompletionStream<R> {
+    fn is_terminated(&self) -> bool {
+        self.inner.is_none()
+    }
+}
+
+//
+impl<R: AsyncRead + Unpin> Stream for CompletionStream<R> {
+    type Item = Result<Vec<u8>, IoError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+
+        let inner = match this.inner.as_mut() {
+            Some(inner) => inner,
+            None => return Poll::Ready(None),
+        };
+
+        let item = ready!(Pin::new(&mut *inner).poll_next(cx));
+        match &item {
+            Some(Ok(chunk)) => this.bytes_read += chunk.len() as u64,
+            Some(Err(err)) => {
+                if inner.is_terminated() {
+                    let kind = err.kind();
+                    this.complete(StreamEnd::Error(kind));
+                }
+            }
+            None => this.complete(StreamEnd::Eof),
+        }
+
+        Poll::Ready(item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures_util::{io::Cursor, StreamExt as _};
+
+    // Fails with `BrokenPipe` instead of EOF.
+    struct FailAtEof<R> {
+        inner: R,
+    }
+
+    impl<R: AsyncRead + Unpin> AsyncRead for FailAtEof<R> {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<Result<usize, IoError>> {
+            match ready!(Pin::new(&mut self.inner).poll_read(cx, buf)) {
+                Ok(0) => Poll::Ready(Err(IoErrorKind::BrokenPipe.into())),
+                x => Poll::Ready(x),
+            }
+        }
+    }
+
+    #[test]
+    fn simple() -> Result<(), Box<dyn std::error::Error>> {
+        futures_executor::block_on(async {
+            let r = Cursor::new(b"1234567890");
+            let (st, rx) = ReaderStream::with_capacity(r, 4).with_completion();
+
+            assert_eq!(st.collect::<Vec<_>>().await.len(), 3);
+            let completed = rx.await?;
+            assert_eq!(completed.bytes_read, 10);
+            assert_eq!(completed.end, StreamEnd::Eof);
+            assert_eq!(completed.reader.position(), 10);
+
+            //
+            let r = Cursor::new(b"1234567890");
+            let (mut st, rx) = ReaderStream::with_capacity(r, 4).with_completion();
+
+            assert_eq!(st.next().await.ok_or("st.next() is_none")??, b"1234");
+            assert_eq!(st.bytes_read(), 4);
+            drop(st);
+            let completed = rx.await?;
+            assert_eq!(completed.bytes_read, 4);
+            assert_eq!(completed.end, StreamEnd::Dropped);
+            assert_eq!(completed.reader.position(), 4);
+
+            //
+            let r = FailAtEof {
+                inner: Cursor::new(b"123456"),
+            };
+            let (st, rx) = ReaderStream::with_capacity(r, 4).with_completion();
+
+            let rets = st.collect::<Vec<_>>().await;
+            assert_eq!(rets.len(), 3);
+            assert_eq!(
+                rets.last()
+                    .and_then(|x| x.as_ref().err())
+                    .map(|err| err.kind()),
+                Some(IoErrorKind::BrokenPipe)
+            );
+            let completed = rx.await?;
+            assert_eq!(completed.bytes_read, 6);
+            assert_eq!(completed.end, StreamEnd::Error(IoErrorKind::BrokenPipe));
+            assert_eq!(completed.reader.inner.position(), 6);
+
+            Ok(())
+        })
+    }
+}
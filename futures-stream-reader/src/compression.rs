@@ -0,0 +1,100 @@
+//! Streams of the compressed or decompressed byte chunks of an [`AsyncRead`], see [`async_compression`].
+//!
+//! `capacity` is used both for the buffer of the reader and for the chunks.
+
+#[cfg(feature = "brotli")]
+use async_compression::futures::bufread::{BrotliDecoder, BrotliEncoder};
+#[cfg(feature = "gzip")]
+use async_compression::futures::bufread::{GzipDecoder, GzipEncoder};
+#[cfg(feature = "zstd")]
+use async_compression::futures::bufread::{ZstdDecoder, ZstdEncoder};
+use futures_util::{io::BufReader, AsyncRead};
+
+use crate::{ReaderStream, DEFAULT_CAPACITY};
+
+//
+macro_rules! codec {
+    ($feature:literal, $fn:ident, $fn_with_capacity:ident, $ty:ident) => {
+        #[cfg(feature = $feature)]
+        pub fn $fn<R: AsyncRead>(reader: R) -> ReaderStream<$ty<BufReader<R>>> {
+            $fn_with_capacity(reader, DEFAULT_CAPACITY)
+        }
+
+        #[cfg(feature = $feature)]
+        pub fn $fn_with_capacity<R: AsyncRead>(
+            reader: R,
+            capacity: usize,
+        ) -> ReaderStream<$ty<BufReader<R>>> {
+            ReaderStream::with_capacity(
+                $ty::new(BufReader::with_capacity(capacity, reader)),
+                capacity,
+            )
+        }
+    };
+}
+
+codec!("gzip", gzip_encode, gzip_encode_with_capacity, GzipEncoder);
+codec!("gzip", gzip_decode, gzip_decode_with_capacity, GzipDecoder);
+codec!("zstd", zstd_encode, zstd_encode_with_capacity, ZstdEncoder);
+codec!("zstd", zstd_decode, zstd_decode_with_capacity, ZstdDecoder);
+codec!(
+    "brotli",
+    brotli_encode,
+    brotli_encode_with_capacity,
+    BrotliEncoder
+);
+codec!(
+    "brotli",
+    brotli_decode,
+    brotli_decode_with_capacity,
+    BrotliDecoder
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Error as IoError;
+
+    use futures_util::{io::Cursor, pin_mut, Stream, StreamExt as _};
+
+    async fn concat(st: impl Stream<Item = Result<Vec<u8>, IoError>>) -> Result<Vec<u8>, IoError> {
+        let mut bytes = vec![];
+        pin_mut!(st);
+        while let Some(chunk) = st.next().await {
+            bytes.extend_from_slice(&chunk?);
+        }
+        Ok(bytes)
+    }
+
+    #[test]
+    fn simple() -> Result<(), Box<dyn std::error::Error>> {
+        futures_executor::block_on(async {
+            let data = b"1234567890".repeat(100);
+
+            #[cfg(feature = "gzip")]
+            {
+                let compressed = concat(gzip_encode_with_capacity(Cursor::new(&data), 16)).await?;
+                assert!(compressed.len() < data.len());
+                let st = gzip_decode_with_capacity(Cursor::new(compressed), 16);
+                assert_eq!(concat(st).await?, data);
+            }
+
+            #[cfg(feature = "zstd")]
+            {
+                let compressed = concat(zstd_encode(Cursor::new(&data))).await?;
+                assert!(compressed.len() < data.len());
+                assert_eq!(concat(zstd_decode(Cursor::new(compressed))).await?, data);
+            }
+
+            #[cfg(feature = "brotli")]
+            {
+                let compressed = concat(brotli_encode(Cursor::new(&data))).await?;
+                assert!(compressed.len() < data.len());
+                assert_eq!(concat(brotli_decode(Cursor::new(compressed))).await?, data);
+            }
+
+            Ok(())
+        })
+    }
+}
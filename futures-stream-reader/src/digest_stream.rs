@@ -0,0 +1,219 @@
+use core::{
+    fmt,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::io::{Error as IoError, ErrorKind as IoErrorKind};
+
+use digest::{Digest, Output};
+use futures_util::{ready, stream::FusedStream, Stream};
+use pin_project_lite::pin_project;
+
+//
+pin_project! {
+    /// Stream of the chunks of a reader stream, e.g. [`crate::ReaderStream`], hashing them as they pass through.
+    ///
+    /// With an expected digest, see [`DigestStream::set_expected`], a mismatch is yielded as an
+    /// [`IoErrorKind::InvalidData`] error at the end of the stream.
+    ///
+    /// After an error of the inner stream the digest is not computed nor verified,
+    /// as some bytes are missing.
+    #[must_use = "streams do nothing unless polled"]
+    pub struct DigestStream<St, D: Digest> {
+        #[pin]
+        inner: St,
+        // None after an error of the inner stream.
+        hasher: Option<D>,
+        expected: Option<Output<D>>,
+        digest: Option<Output<D>>,
+        done: bool,
+    }
+}
+
+impl<St, D: Digest> DigestStream<St, D> {
+    pub fn new(inner: St) -> Self {
+        Self {
+            inner,
+            hasher: Some(D::new()),
+            expected: None,
+            digest: None,
+            done: false,
+        }
+    }
+
+    pub fn set_expected(&mut self, expected: Output<D>) {
+        self.expected = Some(expected);
+    }
+
+    /// The digest of every chunk, once the stream ended without an error.
+    pub fn digest(&self) -> Option<&Output<D>> {
+        self.digest.as_ref()
+    }
+
+    pub fn get_ref(&self) -> &St {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut St {
+        &mut self.inner
+    }
+
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut St> {
+        self.project().inner
+    }
+
+    pub fn into_inner(self) -> St {
+        self.inner
+    }
+}
+
+impl<St: fmt::Debug, D: Digest> fmt::Debug for DigestStream<St, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DigestStream")
+            .field("inner", &self.inner)
+            .field("expected", &self.expected)
+            .field("digest", &self.digest)
+            .finish()
+    }
+}
+
+//
+impl<St, D, B> FusedStream for DigestStream<St, D>
+where
+    St: Stream<Item = Result<B, IoError>>,
+    D: Digest,
+    B: AsRef<[u8]>,
+{
+    fn is_terminated(&self) -> bool {
+        self.done
+    }
+}
+
+//
+impl<St, D, B> Stream for DigestStream<St, D>
+where
+    St: Stream<Item = Result<B, IoError>>,
+    D: Digest,
+    B: AsRef<[u8]>,
+{
+    type Item = Result<B, IoError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+
+        if *this.done {
+            return Poll::Ready(None);
+        }
+
+        match ready!(this.inner.poll_next(cx)) {
+            Some(Ok(chunk)) => {
+                if let Some(hasher) = this.hasher {
+                    hasher.update(chunk.as_ref());
+                }
+                Poll::Ready(Some(Ok(chunk)))
+            }
+            Some(Err(err)) => {
+                *this.hasher = None;
+                Poll::Ready(Some(Err(err)))
+            }
+            None => {
+                *this.done = true;
+
+                let Some(digest) = this.hasher.take().map(D::finalize) else {
+                    return Poll::Ready(None);
+                };
+                let ret = match this.expected {
+                    Some(expected) if *expected != digest => Some(Err(IoError::new(
+                        IoErrorKind::InvalidData,
+                        format!(
+                            "digest mismatch, expected:{}, actual:{}",
+                            to_hex(expected),
+                            to_hex(&digest)
+                        ),
+                    ))),
+                    _ => None,
+                };
+                *this.digest = Some(digest);
+
+                Poll::Ready(ret)
+            }
+        }
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|x| format!("{x:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures_util::{io::Cursor, stream, StreamExt as _};
+    use sha2::Sha256;
+
+    use crate::ReaderStream;
+
+    #[test]
+    fn simple() -> Result<(), Box<dyn std::error::Error>> {
+        futures_executor::block_on(async {
+            let expected = Sha256::digest(b"1234567890");
+
+            let r = Cursor::new(b"1234567890");
+            let mut st = DigestStream::<_, Sha256>::new(ReaderStream::with_capacity(r, 4));
+            st.set_expected(expected);
+            assert!(st.digest().is_none());
+
+            let mut bytes = vec![];
+            while let Some(chunk) = st.next().await {
+                bytes.extend_from_slice(&chunk?);
+            }
+            assert_eq!(bytes, b"1234567890");
+            assert_eq!(st.digest(), Some(&expected));
+            assert!(st.is_terminated());
+
+            //
+            let r = Cursor::new(b"123456789");
+            let mut st = DigestStream::<_, Sha256>::new(ReaderStream::with_capacity(r, 4));
+            st.set_expected(expected);
+
+            let rets = st.by_ref().collect::<Vec<_>>().await;
+            assert_eq!(rets.len(), 4);
+            match rets.last() {
+                Some(Err(err)) => {
+                    assert_eq!(err.kind(), IoErrorKind::InvalidData);
+                    assert!(err
+                        .to_string()
+                        .starts_with("digest mismatch, expected:c775e7b7"));
+                }
+                x => panic!("{x:?}"),
+            }
+            assert_eq!(st.digest(), Some(&Sha256::digest(b"123456789")));
+
+            // Not verified after an error.
+            let inner = stream::iter(vec![
+                Ok(b"12345".to_vec()),
+                Err(IoError::from(IoErrorKind::Other)),
+                Ok(b"67890".to_vec()),
+            ]);
+            let mut st = DigestStream::<_, Sha256>::new(inner);
+            st.set_expected(expected);
+
+            let rets = st.by_ref().collect::<Vec<_>>().await;
+            assert_eq!(
+                rets.into_iter()
+                    .map(|x| x.map_err(|err| err.kind()))
+                    .collect::<Vec<_>>(),
+                vec![
+                    Ok(b"12345".to_vec()),
+                    Err(IoErrorKind::Other),
+                    Ok(b"67890".to_vec())
+                ]
+            );
+            assert!(st.digest().is_none());
+            assert!(st.is_terminated());
+
+            Ok(())
+        })
+    }
+}
@@ -3,7 +3,57 @@
 use core::pin::Pin;
 use std::io::Error as IoError;
 
-use futures_util::{stream::unfold, AsyncRead, AsyncReadExt as _, Stream};
+use futures_util::{AsyncRead, Stream};
+
+#[cfg(feature = "bytes")]
+mod bytes_reader;
+mod chain_stream;
+mod completion;
+#[cfg(any(feature = "gzip", feature = "zstd", feature = "brotli"))]
+pub mod compression;
+#[cfg(feature = "digest")]
+mod digest_stream;
+mod on_error;
+mod progress;
+mod range_stream;
+mod read_state;
+mod read_strategy;
+mod reader_stream;
+mod sink_writer;
+mod split_stream;
+mod stream_reader;
+mod tee;
+mod throttle;
+mod timeout;
+mod timer;
+#[cfg(feature = "tokio_io")]
+pub mod tokio_io;
+mod writer_sink;
+
+#[cfg(feature = "bytes")]
+pub use self::bytes_reader::{
+    bytes_reader, bytes_reader_with_capacity, pooled_reader, pooled_reader_with_capacity,
+    pooled_vectored_reader, pooled_vectored_reader_with_capacity, BufPool, BytesReaderStream,
+    PooledReaderStream, PooledVectoredReaderStream,
+};
+#[cfg(feature = "digest")]
+pub use self::digest_stream::DigestStream;
+pub use self::{
+    chain_stream::{chain, ChainError, ChainStream},
+    completion::{Completed, CompletionStream, StreamEnd},
+    on_error::{ErrorAction, OnError},
+    progress::{Progress, ProgressHandle, ProgressState},
+    range_stream::RangeStream,
+    reader_stream::ReaderStream,
+    sink_writer::SinkWriter,
+    split_stream::{Split, SplitStream, Utf8Stream},
+    stream_reader::StreamReader,
+    tee::{tee, Tee},
+    throttle::Throttle,
+    timeout::Timeout,
+    timer::{MockTimer, Timer},
+    writer_sink::WriterSink,
+};
 
 //
 const DEFAULT_CAPACITY: usize = 4096;
@@ -15,18 +65,13 @@ pub fn reader<R: AsyncRead + Unpin + Send + 'static>(
     reader_with_capacity(reader, DEFAULT_CAPACITY)
 }
 
+/// Errors are yielded and reading continues, use [`ReaderStream`] to control that.
 pub fn reader_with_capacity<R: AsyncRead + Unpin + Send + 'static>(
     reader: R,
     capacity: usize,
 ) -> Pin<Box<dyn Stream<Item = Result<Vec<u8>, IoError>> + Send + 'static>> {
-    let buf = vec![0; capacity];
-    let st = unfold((reader, buf), |(mut reader, mut buf)| async {
-        match reader.read(&mut buf).await {
-            Ok(n) if n == 0 => None,
-            Ok(n) => Some((Ok(buf[..n].to_vec()), (reader, buf))),
-            Err(err) => Some((Err(err), (reader, buf))),
-        }
-    });
+    let mut st = ReaderStream::with_capacity(reader, capacity);
+    st.set_on_error(OnError::Continue);
     Box::pin(st)
 }
 
@@ -36,18 +81,13 @@ pub fn reader_ref<'a, R: AsyncRead + Unpin + Send>(
     reader_ref_with_capacity(reader, DEFAULT_CAPACITY)
 }
 
+/// Errors are yielded and reading continues, use [`ReaderStream`] to control that.
 pub fn reader_ref_with_capacity<'a, R: AsyncRead + Unpin + Send>(
     reader: &'a mut R,
     capacity: usize,
 ) -> Pin<Box<dyn Stream<Item = Result<Vec<u8>, IoError>> + Send + 'a>> {
-    let buf = vec![0; capacity];
-    let st = unfold((reader, buf), |(reader, mut buf)| async {
-        match reader.read(&mut buf).await {
-            Ok(n) if n == 0 => None,
-            Ok(n) => Some((Ok(buf[..n].to_vec()), (reader, buf))),
-            Err(err) => Some((Err(err), (reader, buf))),
-        }
-    });
+    let mut st = ReaderStream::with_capacity(reader, capacity);
+    st.set_on_error(OnError::Continue);
     Box::pin(st)
 }
 
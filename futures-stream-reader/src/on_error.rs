@@ -0,0 +1,65 @@
+use core::fmt;
+use std::{
+    io::{Error as IoError, ErrorKind as IoErrorKind},
+    sync::Arc,
+};
+
+//
+/// What a reader stream does with an error returned by the reader.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorAction {
+    /// Drop the error and read again.
+    ///
+    /// An [`IoErrorKind::Interrupted`] read is retried right away. For any other kind the task is
+    /// woken and the stream returns `Poll::Pending`, so a reader that keeps failing, e.g. with
+    /// [`IoErrorKind::WouldBlock`] without registering a wakeup, is polled in a busy loop.
+    Retry,
+    /// Yield the error and keep reading.
+    Yield,
+    /// Yield the error and end the stream.
+    Terminate,
+}
+
+/// Error handling of a reader stream, see [`crate::ReaderStream::set_on_error`].
+#[derive(Clone, Default)]
+pub enum OnError {
+    /// Every error ends the stream.
+    #[default]
+    Terminate,
+    /// [`IoErrorKind::Interrupted`] and [`IoErrorKind::WouldBlock`] are retried, any other error ends the stream.
+    ///
+    /// See [`ErrorAction::Retry`] about retrying `WouldBlock`.
+    RetryTransient,
+    /// Every error is yielded and reading continues.
+    Continue,
+    Classify(Arc<dyn Fn(&IoError) -> ErrorAction + Send + Sync>),
+}
+
+impl OnError {
+    pub fn classify(f: impl Fn(&IoError) -> ErrorAction + Send + Sync + 'static) -> Self {
+        Self::Classify(Arc::new(f))
+    }
+
+    pub fn action(&self, err: &IoError) -> ErrorAction {
+        match self {
+            Self::Terminate => ErrorAction::Terminate,
+            Self::RetryTransient => match err.kind() {
+                IoErrorKind::Interrupted | IoErrorKind::WouldBlock => ErrorAction::Retry,
+                _ => ErrorAction::Terminate,
+            },
+            Self::Continue => ErrorAction::Yield,
+            Self::Classify(f) => f(err),
+        }
+    }
+}
+
+impl fmt::Debug for OnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Terminate => write!(f, "Terminate"),
+            Self::RetryTransient => write!(f, "RetryTransient"),
+            Self::Continue => write!(f, "Continue"),
+            Self::Classify(_) => write!(f, "Classify"),
+        }
+    }
+}
@@ -0,0 +1,276 @@
+use core::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+use std::{
+    io::Error as IoError,
+    sync::{Arc, Mutex},
+};
+
+use futures_util::{future::poll_fn, ready, stream::FusedStream, Stream};
+use pin_project_lite::pin_project;
+
+//
+/// Progress of a [`Progress`] stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ProgressState {
+    pub bytes_read: u64,
+    pub chunks: u64,
+    pub expected_len: Option<u64>,
+    /// The stream ended.
+    pub finished: bool,
+}
+
+impl ProgressState {
+    /// `bytes_read / expected_len`, if known.
+    pub fn ratio(&self) -> Option<f64> {
+        self.expected_len
+            .filter(|x| *x > 0)
+            .map(|x| self.bytes_read as f64 / x as f64)
+    }
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+    state: ProgressState,
+    version: u64,
+    closed: bool,
+    wakers: Vec<Waker>,
+}
+
+impl Shared {
+    fn wake_all(&mut self) {
+        for waker in self.wakers.drain(..) {
+            waker.wake();
+        }
+    }
+}
+
+// Closes the handles once the stream is dropped.
+#[derive(Debug)]
+struct Publisher(Arc<Mutex<Shared>>);
+
+impl Drop for Publisher {
+    fn drop(&mut self) {
+        let mut shared = self.0.lock().expect("Never poisoned");
+        shared.closed = true;
+        shared.wake_all();
+    }
+}
+
+//
+pin_project! {
+    /// Stream of the chunks of a reader stream, e.g. [`crate::ReaderStream`], counting them as they pass through.
+    ///
+    /// Observe the counts with a callback, see [`Progress::set_on_progress`], or from another task
+    /// with a [`ProgressHandle`].
+    #[must_use = "streams do nothing unless polled"]
+    pub struct Progress<St> {
+        #[pin]
+        inner: St,
+        state: ProgressState,
+        publisher: Publisher,
+        on_progress: Option<Box<dyn FnMut(&ProgressState) + Send>>,
+    }
+}
+
+impl<St> Progress<St> {
+    pub fn new(inner: St) -> Self {
+        Self::with_expected_len(inner, None)
+    }
+
+    pub fn with_expected_len(inner: St, expected_len: Option<u64>) -> Self {
+        let state = ProgressState {
+            expected_len,
+            ..Default::default()
+        };
+
+        Self {
+            inner,
+            state,
+            publisher: Publisher(Arc::new(Mutex::new(Shared {
+                state,
+                ..Default::default()
+            }))),
+            on_progress: None,
+        }
+    }
+
+    /// Called after every chunk and once the stream ends.
+    pub fn set_on_progress(&mut self, on_progress: impl FnMut(&ProgressState) + Send + 'static) {
+        self.on_progress = Some(Box::new(on_progress));
+    }
+
+    pub fn handle(&self) -> ProgressHandle {
+        let shared = self.publisher.0.clone();
+        let seen_version = shared.lock().expect("Never poisoned").version;
+        ProgressHandle {
+            shared,
+            seen_version,
+        }
+    }
+
+    pub fn state(&self) -> ProgressState {
+        self.state
+    }
+
+    pub fn get_ref(&self) -> &St {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut St {
+        &mut self.inner
+    }
+
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut St> {
+        self.project().inner
+    }
+
+    pub fn into_inner(self) -> St {
+        self.inner
+    }
+}
+
+impl<St: fmt::Debug> fmt::Debug for Progress<St> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Progress")
+            .field("inner", &self.inner)
+            .field("state", &self.state)
+            .finish()
+    }
+}
+
+//
+impl<St, B> FusedStream for Progress<St>
+where
+    St: FusedStream<Item = Result<B, IoError>>,
+    B: AsRef<[u8]>,
+{
+    fn is_terminated(&self) -> bool {
+        self.inner.is_terminated()
+    }
+}
+
+//
+impl<St, B> Stream for Progress<St>
+where
+    St: Stream<Item = Result<B, IoError>>,
+    B: AsRef<[u8]>,
+{
+    type Item = Result<B, IoError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+
+        let item = ready!(this.inner.poll_next(cx));
+        match &item {
+            Some(Ok(chunk)) => {
+                this.state.bytes_read += chunk.as_ref().len() as u64;
+                this.state.chunks += 1;
+            }
+            Some(Err(_)) => return Poll::Ready(item),
+            None if this.state.finished => return Poll::Ready(item),
+            None => this.state.finished = true,
+        }
+
+        {
+            let mut shared = this.publisher.0.lock().expect("Never poisoned");
+            shared.state = *this.state;
+            shared.version += 1;
+            shared.wake_all();
+        }
+        if let Some(on_progress) = this.on_progress {
+            on_progress(this.state);
+        }
+
+        Poll::Ready(item)
+    }
+}
+
+//
+//
+//
+/// Watch the [`ProgressState`] of a [`Progress`] stream, e.g. from another task.
+#[derive(Debug, Clone)]
+pub struct ProgressHandle {
+    shared: Arc<Mutex<Shared>>,
+    seen_version: u64,
+}
+
+impl ProgressHandle {
+    pub fn get(&self) -> ProgressState {
+        self.shared.lock().expect("Never poisoned").state
+    }
+
+    /// Wait for a state not seen yet by this handle, `None` once the stream is dropped and every state is seen.
+    pub fn changed(&mut self) -> impl Future<Output = Option<ProgressState>> + '_ {
+        poll_fn(move |cx| {
+            let mut shared = self.shared.lock().expect("Never poisoned");
+
+            if shared.version > self.seen_version {
+                self.seen_version = shared.version;
+                return Poll::Ready(Some(shared.state));
+            }
+            if shared.closed {
+                return Poll::Ready(None);
+            }
+
+            if !shared.wakers.iter().any(|x| x.will_wake(cx.waker())) {
+                shared.wakers.push(cx.waker().clone());
+            }
+            Poll::Pending
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures_util::{future::join, io::Cursor, StreamExt as _};
+
+    use crate::ReaderStream;
+
+    #[test]
+    fn simple() -> Result<(), Box<dyn std::error::Error>> {
+        futures_executor::block_on(async {
+            let r = Cursor::new(b"1234567890");
+            let mut st = Progress::with_expected_len(ReaderStream::with_capacity(r, 4), Some(10));
+
+            let states = Arc::new(Mutex::new(vec![]));
+            st.set_on_progress({
+                let states = states.clone();
+                move |state| states.lock().expect("Never poisoned").push(*state)
+            });
+
+            let mut handle = st.handle();
+            let watch = async move {
+                let mut states = vec![];
+                while let Some(state) = handle.changed().await {
+                    states.push(state);
+                }
+                states
+            };
+
+            let (chunks, watched) = join(st.collect::<Vec<_>>(), watch).await;
+            assert_eq!(chunks.len(), 3);
+
+            let states = states.lock().expect("Never poisoned").clone();
+            assert_eq!(
+                states
+                    .iter()
+                    .map(|x| (x.bytes_read, x.chunks, x.finished))
+                    .collect::<Vec<_>>(),
+                vec![(4, 1, false), (8, 2, false), (10, 3, false), (10, 3, true)]
+            );
+            assert_eq!(states.last().and_then(|x| x.ratio()), Some(1.0));
+
+            // The watcher may skip intermediate states, never the last one.
+            assert_eq!(watched.last(), states.last());
+
+            Ok(())
+        })
+    }
+}
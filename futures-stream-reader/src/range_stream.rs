@@ -0,0 +1,159 @@
+use core::{
+    ops::Range,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::io::{Error as IoError, ErrorKind as IoErrorKind, SeekFrom};
+
+use futures_util::{ready, stream::FusedStream, AsyncRead, AsyncSeek, Stream};
+use pin_project_lite::pin_project;
+
+use crate::{ReaderStream, DEFAULT_CAPACITY};
+
+//
+pin_project! {
+    /// Stream of the byte chunks of `[start, end)` of an [`AsyncRead`] + [`AsyncSeek`], e.g. for HTTP range requests.
+    ///
+    /// Seeks to `start` on the first poll. If the reader ends before `end`,
+    /// an [`IoErrorKind::UnexpectedEof`] error is yielded as the last item.
+    #[derive(Debug)]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct RangeStream<R> {
+        #[pin]
+        inner: ReaderStream<R>,
+        start: u64,
+        n_more: u64,
+        seeked: bool,
+        done: bool,
+    }
+}
+
+impl<R: AsyncRead + AsyncSeek> RangeStream<R> {
+    pub fn new(reader: R, range: Range<u64>) -> Self {
+        Self::with_capacity(reader, range, DEFAULT_CAPACITY)
+    }
+
+    pub fn with_capacity(reader: R, range: Range<u64>, capacity: usize) -> Self {
+        let n_more = range.end.saturating_sub(range.start);
+
+        let mut inner = ReaderStream::with_capacity(reader, capacity);
+        inner.set_limit(Some(n_more));
+
+        Self {
+            inner,
+            start: range.start,
+            n_more,
+            seeked: false,
+            done: false,
+        }
+    }
+}
+
+impl<R> RangeStream<R> {
+    pub fn get_ref(&self) -> &R {
+        self.inner.get_ref()
+    }
+
+    pub fn get_mut(&mut self) -> &mut R {
+        self.inner.get_mut()
+    }
+
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut R> {
+        self.project().inner.get_pin_mut()
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner.into_inner()
+    }
+}
+
+//
+impl<R: AsyncRead + AsyncSeek> FusedStream for RangeStream<R> {
+    fn is_terminated(&self) -> bool {
+        self.done
+    }
+}
+
+//
+impl<R: AsyncRead + AsyncSeek> Stream for RangeStream<R> {
+    type Item = Result<Vec<u8>, IoError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        if *this.done {
+            return Poll::Ready(None);
+        }
+
+        if !*this.seeked {
+            let ret = ready!(this
+                .inner
+                .as_mut()
+                .get_pin_mut()
+                .poll_seek(cx, SeekFrom::Start(*this.start)));
+            if let Err(err) = ret {
+                *this.done = true;
+                return Poll::Ready(Some(Err(err)));
+            }
+            *this.seeked = true;
+        }
+
+        match ready!(this.inner.poll_next(cx)) {
+            Some(Ok(chunk)) => {
+                *this.n_more -= chunk.len() as u64;
+                Poll::Ready(Some(Ok(chunk)))
+            }
+            Some(Err(err)) => {
+                *this.done = true;
+                Poll::Ready(Some(Err(err)))
+            }
+            None => {
+                *this.done = true;
+                if *this.n_more > 0 {
+                    Poll::Ready(Some(Err(IoError::new(
+                        IoErrorKind::UnexpectedEof,
+                        format!("unexpected eof, n_more:{}", this.n_more),
+                    ))))
+                } else {
+                    Poll::Ready(None)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures_util::{io::Cursor, StreamExt as _};
+
+    #[test]
+    fn simple() -> Result<(), Box<dyn std::error::Error>> {
+        futures_executor::block_on(async {
+            let r = Cursor::new(b"1234567890");
+            let mut st = RangeStream::with_capacity(r, 2..7, 2);
+            assert_eq!(st.next().await.ok_or("st.next() is_none")??, b"34");
+            assert_eq!(st.next().await.ok_or("st.next() is_none")??, b"56");
+            assert_eq!(st.next().await.ok_or("st.next() is_none")??, b"7");
+            assert!(st.next().await.is_none());
+            assert!(st.is_terminated());
+            assert_eq!(st.into_inner().position(), 7);
+
+            //
+            let mut r = Cursor::new(b"1234567890");
+            let mut st = RangeStream::with_capacity(&mut r, 8..20, 4);
+            assert_eq!(st.next().await.ok_or("st.next() is_none")??, b"90");
+            match st.next().await {
+                Some(Err(err)) => {
+                    assert_eq!(err.kind(), IoErrorKind::UnexpectedEof);
+                    assert_eq!(err.to_string(), "unexpected eof, n_more:10");
+                }
+                x => panic!("{x:?}"),
+            }
+            assert!(st.next().await.is_none());
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,105 @@
+use std::io::{Error as IoError, ErrorKind as IoErrorKind};
+
+use crate::{
+    on_error::{ErrorAction, OnError},
+    read_strategy::ReadStrategy,
+};
+
+//
+/// The state a reader stream keeps around its reads, whatever the kind of reader.
+#[derive(Debug)]
+pub(crate) struct ReadState {
+    strategy: ReadStrategy,
+    limit: Option<u64>,
+    on_error: OnError,
+    done: bool,
+}
+
+/// What to do with the result of a read.
+#[derive(Debug)]
+pub(crate) enum ReadRet {
+    /// Yield the first `n` bytes read.
+    Chunk(usize),
+    Eof,
+    Err(IoError),
+    /// Read again right away, the read was interrupted.
+    Retry,
+    /// Wake the task and return [`Poll::Pending`](core::task::Poll::Pending), then read again.
+    RetryLater,
+}
+
+impl ReadState {
+    pub(crate) fn new(strategy: ReadStrategy) -> Self {
+        Self {
+            strategy,
+            limit: None,
+            on_error: OnError::default(),
+            done: false,
+        }
+    }
+
+    pub(crate) fn set_on_error(&mut self, on_error: OnError) {
+        self.on_error = on_error;
+    }
+
+    pub(crate) fn set_limit(&mut self, limit: Option<u64>) {
+        self.limit = limit;
+    }
+
+    pub(crate) fn read_size(&self) -> usize {
+        self.strategy.next()
+    }
+
+    pub(crate) fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// End the stream for a reason other than a read.
+    pub(crate) fn terminate(&mut self) {
+        self.done = true;
+    }
+
+    /// The number of bytes to read next, at most [`ReadState::read_size`].
+    /// `None` once the stream has ended.
+    pub(crate) fn begin(&mut self) -> Option<usize> {
+        if self.done {
+            return None;
+        }
+
+        if self.limit == Some(0) {
+            self.done = true;
+            return None;
+        }
+
+        let read_size = self.strategy.next();
+        Some(
+            self.limit
+                .map_or(read_size, |x| x.min(read_size as u64) as usize),
+        )
+    }
+
+    pub(crate) fn finish(&mut self, ret: Result<usize, IoError>) -> ReadRet {
+        match ret {
+            Ok(0) => {
+                self.done = true;
+                ReadRet::Eof
+            }
+            Ok(n) => {
+                self.strategy.record(n);
+                if let Some(limit) = &mut self.limit {
+                    *limit -= n as u64;
+                }
+                ReadRet::Chunk(n)
+            }
+            Err(err) => match self.on_error.action(&err) {
+                ErrorAction::Retry if err.kind() == IoErrorKind::Interrupted => ReadRet::Retry,
+                ErrorAction::Retry => ReadRet::RetryLater,
+                ErrorAction::Yield => ReadRet::Err(err),
+                ErrorAction::Terminate => {
+                    self.done = true;
+                    ReadRet::Err(err)
+                }
+            },
+        }
+    }
+}
@@ -0,0 +1,102 @@
+// Ref https://github.com/hyperium/hyper/blob/v0.14.27/src/proto/h1/io.rs
+
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ReadStrategy {
+    Exact(usize),
+    /// Doubles the read size after a read fills it, halves it after two reads in a row fit in the half.
+    Adaptive {
+        min: usize,
+        max: usize,
+        next: usize,
+        decrease_now: bool,
+    },
+}
+
+impl ReadStrategy {
+    pub(crate) fn adaptive(min: usize, max: usize) -> Self {
+        let min = min.max(1);
+        let max = max.max(min);
+        Self::Adaptive {
+            min,
+            max,
+            next: min,
+            decrease_now: false,
+        }
+    }
+
+    pub(crate) fn next(&self) -> usize {
+        match self {
+            Self::Exact(n) => *n,
+            Self::Adaptive { next, .. } => *next,
+        }
+    }
+
+    pub(crate) fn record(&mut self, n_read: usize) {
+        if let Self::Adaptive {
+            min,
+            max,
+            next,
+            decrease_now,
+        } = self
+        {
+            if n_read >= *next {
+                *next = next.saturating_mul(2).min(*max);
+                *decrease_now = false;
+            } else {
+                let decr_to = (*next / 2).max(*min);
+                if n_read <= decr_to && decr_to < *next {
+                    if *decrease_now {
+                        *next = decr_to;
+                        *decrease_now = false;
+                    } else {
+                        *decrease_now = true;
+                    }
+                } else {
+                    *decrease_now = false;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adaptive() {
+        let mut strategy = ReadStrategy::adaptive(8, 64);
+        assert_eq!(strategy.next(), 8);
+
+        for expected in [16, 32, 64, 64] {
+            strategy.record(strategy.next());
+            assert_eq!(strategy.next(), expected);
+        }
+
+        // Shrinks after two small reads in a row.
+        strategy.record(10);
+        assert_eq!(strategy.next(), 64);
+        strategy.record(10);
+        assert_eq!(strategy.next(), 32);
+
+        // A read in between resets it.
+        strategy.record(10);
+        strategy.record(20);
+        strategy.record(10);
+        assert_eq!(strategy.next(), 32);
+        strategy.record(10);
+        assert_eq!(strategy.next(), 16);
+
+        strategy.record(1);
+        strategy.record(1);
+        assert_eq!(strategy.next(), 8);
+        strategy.record(1);
+        strategy.record(1);
+        assert_eq!(strategy.next(), 8);
+
+        let mut strategy = ReadStrategy::Exact(4);
+        strategy.record(4);
+        assert_eq!(strategy.next(), 4);
+    }
+}
@@ -0,0 +1,287 @@
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::io::Error as IoError;
+
+use futures_util::{ready, stream::FusedStream, AsyncRead, Stream};
+use pin_project_lite::pin_project;
+
+use crate::{
+    on_error::OnError,
+    read_state::{ReadRet, ReadState},
+    read_strategy::ReadStrategy,
+    DEFAULT_CAPACITY,
+};
+
+//
+pin_project! {
+    /// Stream of the byte chunks read from an [`AsyncRead`], it ends at EOF.
+    ///
+    /// By default it also ends after the first error, see [`ReaderStream::set_on_error`].
+    #[derive(Debug)]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct ReaderStream<R> {
+        #[pin]
+        reader: R,
+        buf: Vec<u8>,
+        state: ReadState,
+    }
+}
+
+impl<R: AsyncRead> ReaderStream<R> {
+    pub fn new(reader: R) -> Self {
+        Self::with_capacity(reader, DEFAULT_CAPACITY)
+    }
+
+    pub fn with_capacity(reader: R, capacity: usize) -> Self {
+        Self {
+            reader,
+            buf: vec![0; capacity],
+            state: ReadState::new(ReadStrategy::Exact(capacity)),
+        }
+    }
+
+    /// The read size starts at `min`, doubles up to `max` while reads fill it,
+    /// and halves down to `min` while reads are small.
+    pub fn with_adaptive_capacity(reader: R, min: usize, max: usize) -> Self {
+        let strategy = ReadStrategy::adaptive(min, max);
+        Self {
+            reader,
+            buf: vec![0; strategy.next()],
+            state: ReadState::new(strategy),
+        }
+    }
+}
+
+impl<R> ReaderStream<R> {
+    pub fn set_on_error(&mut self, on_error: OnError) {
+        self.state.set_on_error(on_error);
+    }
+
+    /// Read at most `limit` more bytes, then end as at EOF.
+    pub fn set_limit(&mut self, limit: Option<u64>) {
+        self.state.set_limit(limit);
+    }
+
+    /// The size of the next read.
+    pub fn read_size(&self) -> usize {
+        self.state.read_size()
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut R> {
+        self.project().reader
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+//
+impl<R: AsyncRead> FusedStream for ReaderStream<R> {
+    fn is_terminated(&self) -> bool {
+        self.state.is_done()
+    }
+}
+
+//
+impl<R: AsyncRead> Stream for ReaderStream<R> {
+    type Item = Result<Vec<u8>, IoError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        loop {
+            let Some(len) = this.state.begin() else {
+                return Poll::Ready(None);
+            };
+
+            let read_size = this.state.read_size();
+            if this.buf.len() != read_size {
+                this.buf.resize(read_size, 0);
+                this.buf.shrink_to(read_size);
+            }
+
+            let ret = ready!(this.reader.as_mut().poll_read(cx, &mut this.buf[..len]));
+            return match this.state.finish(ret) {
+                ReadRet::Chunk(n) => Poll::Ready(Some(Ok(this.buf[..n].to_vec()))),
+                ReadRet::Eof => Poll::Ready(None),
+                ReadRet::Err(err) => Poll::Ready(Some(Err(err))),
+                ReadRet::Retry => continue,
+                ReadRet::RetryLater => {
+                    cx.waker().wake_by_ref();
+                    Poll::Pending
+                }
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{io::ErrorKind as IoErrorKind, rc::Rc};
+
+    use futures_util::{io::Cursor, FutureExt as _, StreamExt as _};
+
+    use crate::on_error::ErrorAction;
+
+    #[test]
+    fn simple() -> Result<(), Box<dyn std::error::Error>> {
+        futures_executor::block_on(async {
+            // Rc makes the reader !Send.
+            let r = Cursor::new(Rc::<[u8]>::from(&b"1234567890"[..]));
+
+            let mut st = ReaderStream::with_capacity(r, 4);
+            assert!(!st.is_terminated());
+            assert_eq!(st.next().await.ok_or("st.next() is_none")??, b"1234");
+            assert_eq!(st.next().await.ok_or("st.next() is_none")??, b"5678");
+            assert_eq!(st.next().await.ok_or("st.next() is_none")??, b"90");
+            assert!(st.next().await.is_none());
+            assert!(st.is_terminated());
+            assert!(st.next().await.is_none());
+
+            assert_eq!(st.into_inner().position(), 10);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_limit() -> Result<(), Box<dyn std::error::Error>> {
+        futures_executor::block_on(async {
+            let mut r = Cursor::new(b"1234567890");
+
+            let mut st = ReaderStream::with_capacity(&mut r, 4);
+            st.set_limit(Some(6));
+            assert_eq!(st.next().await.ok_or("st.next() is_none")??, b"1234");
+            assert_eq!(st.next().await.ok_or("st.next() is_none")??, b"56");
+            assert!(st.next().await.is_none());
+            assert_eq!(r.position(), 6);
+
+            let mut st = ReaderStream::with_capacity(&mut r, 4);
+            st.set_limit(Some(10));
+            assert_eq!(st.next().await.ok_or("st.next() is_none")??, b"7890");
+            assert!(st.next().await.is_none());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_adaptive_capacity() -> Result<(), Box<dyn std::error::Error>> {
+        futures_executor::block_on(async {
+            let r = Cursor::new(vec![1; 8 + 16 + 32 + 64 + 64 + 10]);
+
+            let mut st = ReaderStream::with_adaptive_capacity(r, 8, 64);
+            assert_eq!(st.read_size(), 8);
+            let mut lens = vec![];
+            while let Some(chunk) = st.next().await {
+                lens.push(chunk?.len());
+            }
+            assert_eq!(lens, [8, 16, 32, 64, 64, 10]);
+            assert_eq!(st.read_size(), 64);
+
+            Ok(())
+        })
+    }
+
+    // Fails with the given kinds in order, then reads from the inner reader.
+    struct Failing<R> {
+        inner: R,
+        kinds: Vec<IoErrorKind>,
+    }
+
+    impl<R: AsyncRead + Unpin> AsyncRead for Failing<R> {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<Result<usize, IoError>> {
+            if !self.kinds.is_empty() {
+                let kind = self.kinds.remove(0);
+                return Poll::Ready(Err(kind.into()));
+            }
+            Pin::new(&mut self.inner).poll_read(cx, buf)
+        }
+    }
+
+    #[test]
+    fn test_on_error() -> Result<(), Box<dyn std::error::Error>> {
+        futures_executor::block_on(async {
+            let new = || Failing {
+                inner: Cursor::new(b"123"),
+                kinds: vec![
+                    IoErrorKind::Interrupted,
+                    IoErrorKind::WouldBlock,
+                    IoErrorKind::Other,
+                ],
+            };
+            let kinds = |rets: Vec<Result<Vec<u8>, IoError>>| {
+                rets.into_iter()
+                    .map(|x| x.map_err(|err| err.kind()))
+                    .collect::<Vec<_>>()
+            };
+
+            let st = ReaderStream::new(new());
+            assert_eq!(
+                kinds(st.collect().await),
+                vec![Err(IoErrorKind::Interrupted)]
+            );
+
+            let mut st = ReaderStream::new(new());
+            st.set_on_error(OnError::RetryTransient);
+            assert_eq!(kinds(st.collect().await), vec![Err(IoErrorKind::Other)]);
+
+            let mut st = ReaderStream::new(new());
+            st.set_on_error(OnError::Continue);
+            assert_eq!(
+                kinds(st.collect().await),
+                vec![
+                    Err(IoErrorKind::Interrupted),
+                    Err(IoErrorKind::WouldBlock),
+                    Err(IoErrorKind::Other),
+                    Ok(b"123".to_vec())
+                ]
+            );
+
+            let mut st = ReaderStream::new(new());
+            st.set_on_error(OnError::classify(|err| match err.kind() {
+                IoErrorKind::Interrupted => ErrorAction::Retry,
+                IoErrorKind::WouldBlock => ErrorAction::Yield,
+                _ => ErrorAction::Terminate,
+            }));
+            assert_eq!(
+                kinds((&mut st).collect().await),
+                vec![Err(IoErrorKind::WouldBlock), Err(IoErrorKind::Other)]
+            );
+            assert!(st.is_terminated());
+
+            // Interrupted is retried within the same poll.
+            let mut st = ReaderStream::new(Failing {
+                inner: Cursor::new(b"123"),
+                kinds: vec![IoErrorKind::Interrupted; 3],
+            });
+            st.set_on_error(OnError::RetryTransient);
+            assert_eq!(
+                st.next()
+                    .now_or_never()
+                    .flatten()
+                    .ok_or("st.next() is_pending")??,
+                b"123"
+            );
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,177 @@
+use core::{
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::io::Error as IoError;
+
+use futures_util::{ready, AsyncWrite, Sink};
+use pin_project_lite::pin_project;
+
+use crate::DEFAULT_CAPACITY;
+
+//
+pin_project! {
+    /// Writer into a [`Sink`] of byte chunks, e.g. `Sink<Vec<u8>>` or `Sink<Bytes>`.
+    ///
+    /// Written bytes are buffered up to `chunk_size`, a chunk is sent once it is full or on `flush`/`close`.
+    #[derive(Debug)]
+    pub struct SinkWriter<Si, T> {
+        #[pin]
+        sink: Si,
+        buf: Vec<u8>,
+        chunk_size: usize,
+        _marker: PhantomData<fn(T)>,
+    }
+}
+
+impl<Si, T> SinkWriter<Si, T>
+where
+    Si: Sink<T>,
+    Si::Error: Into<IoError>,
+    T: From<Vec<u8>>,
+{
+    pub fn new(sink: Si) -> Self {
+        Self::with_chunk_size(sink, DEFAULT_CAPACITY)
+    }
+
+    pub fn with_chunk_size(sink: Si, chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "chunk_size must be greater than 0");
+
+        Self {
+            sink,
+            buf: Vec::with_capacity(chunk_size),
+            chunk_size,
+            _marker: PhantomData,
+        }
+    }
+
+    fn poll_send_chunk(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), IoError>> {
+        let mut this = self.project();
+
+        if this.buf.is_empty() {
+            return Poll::Ready(Ok(()));
+        }
+
+        ready!(this.sink.as_mut().poll_ready(cx)).map_err(Into::into)?;
+        let chunk = core::mem::replace(this.buf, Vec::with_capacity(*this.chunk_size));
+        this.sink
+            .as_mut()
+            .start_send(T::from(chunk))
+            .map_err(Into::into)?;
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<Si, T> SinkWriter<Si, T> {
+    pub fn get_ref(&self) -> &Si {
+        &self.sink
+    }
+
+    pub fn get_mut(&mut self) -> &mut Si {
+        &mut self.sink
+    }
+
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut Si> {
+        self.project().sink
+    }
+
+    /// Bytes written but not yet sent are lost, call `close` or `flush` first.
+    pub fn into_inner(self) -> Si {
+        self.sink
+    }
+}
+
+//
+impl<Si, T> AsyncWrite for SinkWriter<Si, T>
+where
+    Si: Sink<T>,
+    Si::Error: Into<IoError>,
+    T: From<Vec<u8>>,
+{
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<Result<usize, IoError>> {
+        if self.buf.len() >= self.chunk_size {
+            ready!(self.as_mut().poll_send_chunk(cx))?;
+        }
+
+        let this = self.project();
+
+        let n = core::cmp::min(buf.len(), *this.chunk_size - this.buf.len());
+        this.buf.extend_from_slice(&buf[..n]);
+
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), IoError>> {
+        ready!(self.as_mut().poll_send_chunk(cx))?;
+
+        let this = self.project();
+        this.sink.poll_flush(cx).map_err(Into::into)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), IoError>> {
+        ready!(self.as_mut().poll_send_chunk(cx))?;
+
+        let this = self.project();
+        this.sink.poll_close(cx).map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures_util::io::AsyncWriteExt as _;
+
+    struct Chunks(Vec<Vec<u8>>);
+
+    impl Sink<Vec<u8>> for Chunks {
+        type Error = IoError;
+
+        fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), IoError>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn start_send(mut self: Pin<&mut Self>, item: Vec<u8>) -> Result<(), IoError> {
+            self.0.push(item);
+            Ok(())
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), IoError>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), IoError>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[test]
+    fn simple() -> Result<(), Box<dyn std::error::Error>> {
+        futures_executor::block_on(async {
+            let mut w = SinkWriter::with_chunk_size(Chunks(vec![]), 4);
+            w.write_all(b"abcdefghij").await?;
+            w.flush().await?;
+            w.write_all(b"k").await?;
+            w.write_all(b"lm").await?;
+            w.close().await?;
+
+            assert_eq!(
+                w.into_inner().0,
+                vec![
+                    b"abcd".to_vec(),
+                    b"efgh".to_vec(),
+                    b"ij".to_vec(),
+                    b"klm".to_vec()
+                ]
+            );
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,421 @@
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::io::{Error as IoError, ErrorKind as IoErrorKind};
+
+use futures_util::{ready, stream::FusedStream, AsyncRead, Stream};
+use pin_project_lite::pin_project;
+
+use crate::{
+    on_error::OnError,
+    read_state::{ReadRet, ReadState},
+    read_strategy::ReadStrategy,
+    DEFAULT_CAPACITY,
+};
+
+//
+/// Where [`SplitStream`] splits the bytes read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Split {
+    /// On every `delimiter`, which is not part of the record.
+    Delimiter(u8),
+    /// On every `\n` or `\r\n`, which is not part of the line. A `\r` ending the last line is stripped too.
+    Lines,
+    /// Every `n` bytes.
+    Fixed(usize),
+}
+
+pin_project! {
+    /// Stream of the records read from an [`AsyncRead`], records may span several reads.
+    ///
+    /// The last record is yielded at EOF even if it is not terminated, or shorter than [`Split::Fixed`].
+    #[derive(Debug)]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct SplitStream<R> {
+        #[pin]
+        reader: R,
+        split: Split,
+        buf: Vec<u8>,
+        pending: Vec<u8>,
+        // pending[..start] is already yielded, it is dropped once per read.
+        start: usize,
+        n_searched: usize,
+        max_len: Option<usize>,
+        state: ReadState,
+    }
+}
+
+impl<R: AsyncRead> SplitStream<R> {
+    pub fn new(reader: R, split: Split) -> Self {
+        Self::with_capacity(reader, split, DEFAULT_CAPACITY)
+    }
+
+    pub fn with_capacity(reader: R, split: Split, capacity: usize) -> Self {
+        if let Split::Fixed(n) = split {
+            assert!(n > 0, "record size must be greater than 0");
+        }
+
+        Self {
+            reader,
+            split,
+            buf: vec![0; capacity],
+            pending: vec![],
+            start: 0,
+            n_searched: 0,
+            max_len: None,
+            state: ReadState::new(ReadStrategy::Exact(capacity)),
+        }
+    }
+
+    /// Yield the records as [`String`]s, invalid UTF-8 is an [`IoErrorKind::InvalidData`] error.
+    pub fn utf8(self) -> Utf8Stream<Self> {
+        Utf8Stream { inner: self }
+    }
+}
+
+impl<R> SplitStream<R> {
+    /// A longer [`Split::Delimiter`] or [`Split::Lines`] record is an [`IoErrorKind::InvalidData`] error
+    /// that ends the stream.
+    pub fn set_max_len(&mut self, max_len: Option<usize>) {
+        self.max_len = max_len;
+    }
+
+    pub fn set_on_error(&mut self, on_error: OnError) {
+        self.state.set_on_error(on_error);
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut R> {
+        self.project().reader
+    }
+
+    /// Bytes read but not yet yielded are lost.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl Split {
+    // (record_len, consumed_len)
+    fn find(&self, pending: &[u8], n_searched: usize) -> Option<(usize, usize)> {
+        match self {
+            Self::Delimiter(delimiter) => pending[n_searched..]
+                .iter()
+                .position(|b| b == delimiter)
+                .map(|i| (n_searched + i, n_searched + i + 1)),
+            Self::Lines => pending[n_searched..]
+                .iter()
+                .position(|b| *b == b'\n')
+                .map(|i| {
+                    let i = n_searched + i;
+                    if i > 0 && pending[i - 1] == b'\r' {
+                        (i - 1, i + 1)
+                    } else {
+                        (i, i + 1)
+                    }
+                }),
+            Self::Fixed(n) => (pending.len() >= *n).then_some((*n, *n)),
+        }
+    }
+
+    fn max_record_len(&self, max_len: usize) -> Option<usize> {
+        match self {
+            Self::Delimiter(_) | Self::Lines => Some(max_len),
+            Self::Fixed(_) => None,
+        }
+    }
+
+    // The last record at EOF.
+    fn trim_last(&self, record: &mut Vec<u8>) {
+        if let Self::Lines = self {
+            if record.ends_with(b"\r") {
+                record.pop();
+            }
+        }
+    }
+
+    fn max_pending_len(&self, max_len: usize) -> Option<usize> {
+        match self {
+            Self::Delimiter(_) => Some(max_len),
+            // The `\r` of a `\r\n` may be pending.
+            Self::Lines => Some(max_len + 1),
+            Self::Fixed(_) => None,
+        }
+    }
+}
+
+//
+impl<R: AsyncRead> FusedStream for SplitStream<R> {
+    fn is_terminated(&self) -> bool {
+        self.state.is_done()
+    }
+}
+
+//
+impl<R: AsyncRead> Stream for SplitStream<R> {
+    type Item = Result<Vec<u8>, IoError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        loop {
+            if this.state.is_done() {
+                return Poll::Ready(None);
+            }
+
+            let pending = &this.pending[*this.start..];
+            match this.split.find(pending, *this.n_searched) {
+                Some((record_len, consumed_len)) => {
+                    *this.n_searched = 0;
+
+                    if let Some(max_len) = *this.max_len {
+                        if this
+                            .split
+                            .max_record_len(max_len)
+                            .is_some_and(|x| record_len > x)
+                        {
+                            this.state.terminate();
+                            return Poll::Ready(Some(Err(too_long(max_len))));
+                        }
+                    }
+
+                    let record = pending[..record_len].to_vec();
+                    *this.start += consumed_len;
+                    return Poll::Ready(Some(Ok(record)));
+                }
+                None => {
+                    *this.n_searched = pending.len();
+
+                    if let Some(max_len) = *this.max_len {
+                        if this
+                            .split
+                            .max_pending_len(max_len)
+                            .is_some_and(|x| pending.len() > x)
+                        {
+                            this.state.terminate();
+                            return Poll::Ready(Some(Err(too_long(max_len))));
+                        }
+                    }
+                }
+            }
+
+            this.pending.drain(..*this.start);
+            *this.start = 0;
+
+            let Some(len) = this.state.begin() else {
+                return Poll::Ready(None);
+            };
+
+            let ret = ready!(this.reader.as_mut().poll_read(cx, &mut this.buf[..len]));
+            match this.state.finish(ret) {
+                ReadRet::Chunk(n) => this.pending.extend_from_slice(&this.buf[..n]),
+                ReadRet::Eof => {
+                    if this.pending.is_empty() {
+                        return Poll::Ready(None);
+                    }
+
+                    let mut record = core::mem::take(this.pending);
+                    this.split.trim_last(&mut record);
+
+                    if let Some(max_len) = *this.max_len {
+                        if this
+                            .split
+                            .max_record_len(max_len)
+                            .is_some_and(|x| record.len() > x)
+                        {
+                            return Poll::Ready(Some(Err(too_long(max_len))));
+                        }
+                    }
+
+                    return Poll::Ready(Some(Ok(record)));
+                }
+                ReadRet::Err(err) => return Poll::Ready(Some(Err(err))),
+                ReadRet::Retry => {}
+                ReadRet::RetryLater => {
+                    cx.waker().wake_by_ref();
+                    return Poll::Pending;
+                }
+            }
+        }
+    }
+}
+
+fn too_long(max_len: usize) -> IoError {
+    IoError::new(
+        IoErrorKind::InvalidData,
+        format!("record too long, max_len:{max_len}"),
+    )
+}
+
+//
+//
+//
+pin_project! {
+    /// Stream for the [`SplitStream::utf8()`] method. See method docs for details.
+    #[derive(Debug)]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct Utf8Stream<St> {
+        #[pin]
+        inner: St,
+    }
+}
+
+impl<St> Utf8Stream<St> {
+    pub fn get_ref(&self) -> &St {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut St {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> St {
+        self.inner
+    }
+}
+
+impl<St> FusedStream for Utf8Stream<St>
+where
+    St: FusedStream<Item = Result<Vec<u8>, IoError>>,
+{
+    fn is_terminated(&self) -> bool {
+        self.inner.is_terminated()
+    }
+}
+
+impl<St> Stream for Utf8Stream<St>
+where
+    St: Stream<Item = Result<Vec<u8>, IoError>>,
+{
+    type Item = Result<String, IoError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+
+        match ready!(this.inner.poll_next(cx)) {
+            Some(Ok(record)) => Poll::Ready(Some(
+                String::from_utf8(record)
+                    .map_err(|err| IoError::new(IoErrorKind::InvalidData, err)),
+            )),
+            Some(Err(err)) => Poll::Ready(Some(Err(err))),
+            None => Poll::Ready(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures_util::{io::Cursor, StreamExt as _};
+
+    async fn collect<St: Stream<Item = Result<T, IoError>>, T>(
+        st: St,
+    ) -> Vec<Result<T, IoErrorKind>> {
+        st.map(|x| x.map_err(|err| err.kind())).collect().await
+    }
+
+    #[test]
+    fn simple() {
+        futures_executor::block_on(async {
+            // A small capacity makes records and `\r\n` span several reads.
+            let st = SplitStream::with_capacity(Cursor::new(b"ab\r\ncd\n\nefgh"), Split::Lines, 3);
+            assert_eq!(
+                collect(st).await,
+                vec![
+                    Ok(b"ab".to_vec()),
+                    Ok(b"cd".to_vec()),
+                    Ok(b"".to_vec()),
+                    Ok(b"efgh".to_vec())
+                ]
+            );
+
+            let st = SplitStream::with_capacity(Cursor::new(b"abc,de,"), Split::Delimiter(b','), 2);
+            assert_eq!(
+                collect(st).await,
+                vec![Ok(b"abc".to_vec()), Ok(b"de".to_vec())]
+            );
+
+            let st = SplitStream::with_capacity(Cursor::new(b"1234567"), Split::Fixed(3), 2);
+            assert_eq!(
+                collect(st).await,
+                vec![Ok(b"123".to_vec()), Ok(b"456".to_vec()), Ok(b"7".to_vec())]
+            );
+
+            let st = SplitStream::new(Cursor::new(b""), Split::Lines);
+            assert_eq!(collect(st).await, vec![]);
+
+            // Many records in one read.
+            let st = SplitStream::new(Cursor::new(b"a\nb\nc\nd"), Split::Lines);
+            assert_eq!(
+                collect(st).await,
+                vec![
+                    Ok(b"a".to_vec()),
+                    Ok(b"b".to_vec()),
+                    Ok(b"c".to_vec()),
+                    Ok(b"d".to_vec())
+                ]
+            );
+        })
+    }
+
+    #[test]
+    fn test_max_len() {
+        futures_executor::block_on(async {
+            let mut st = SplitStream::with_capacity(Cursor::new(b"abc\r\nabcd\n"), Split::Lines, 4);
+            st.set_max_len(Some(3));
+            assert_eq!(
+                collect(st).await,
+                vec![Ok(b"abc".to_vec()), Err(IoErrorKind::InvalidData)]
+            );
+
+            let mut st = SplitStream::new(Cursor::new(b"abcdefgh"), Split::Delimiter(b','));
+            st.set_max_len(Some(3));
+            assert_eq!(collect(st).await, vec![Err(IoErrorKind::InvalidData)]);
+
+            // Also applied to the last record at EOF.
+            let mut st = SplitStream::new(Cursor::new(b"abcd"), Split::Lines);
+            st.set_max_len(Some(3));
+            assert_eq!(collect(st).await, vec![Err(IoErrorKind::InvalidData)]);
+
+            let mut st = SplitStream::new(Cursor::new(b"abc\r"), Split::Lines);
+            st.set_max_len(Some(3));
+            assert_eq!(collect(st).await, vec![Ok(b"abc".to_vec())]);
+
+            let mut st = SplitStream::new(Cursor::new(b"abcd\r"), Split::Lines);
+            st.set_max_len(Some(3));
+            assert_eq!(collect(st).await, vec![Err(IoErrorKind::InvalidData)]);
+
+            // Not applied to fixed size records.
+            let mut st = SplitStream::new(Cursor::new(b"abcdefgh"), Split::Fixed(5));
+            st.set_max_len(Some(3));
+            assert_eq!(
+                collect(st).await,
+                vec![Ok(b"abcde".to_vec()), Ok(b"fgh".to_vec())]
+            );
+        })
+    }
+
+    #[test]
+    fn test_utf8() {
+        futures_executor::block_on(async {
+            let st = SplitStream::new(Cursor::new(b"ab\n\xff\ncd".to_vec()), Split::Lines).utf8();
+            assert_eq!(
+                collect(st).await,
+                vec![
+                    Ok("ab".to_string()),
+                    Err(IoErrorKind::InvalidData),
+                    Ok("cd".to_string())
+                ]
+            );
+        })
+    }
+}
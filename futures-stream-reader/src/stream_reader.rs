@@ -0,0 +1,250 @@
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::io::Error as IoError;
+
+use futures_util::{ready, AsyncBufRead, AsyncRead, Stream};
+use pin_project_lite::pin_project;
+
+//
+pin_project! {
+    /// Reader of the byte chunks of a [`Stream`], the inverse of [`crate::ReaderStream`].
+    ///
+    /// Implements [`AsyncRead`] and [`AsyncBufRead`], and their tokio counterparts with the `tokio_io` feature.
+    #[derive(Debug)]
+    pub struct StreamReader<S, B> {
+        #[pin]
+        stream: S,
+        chunk: Option<B>,
+        pos: usize,
+    }
+}
+
+impl<S, B, E> StreamReader<S, B>
+where
+    S: Stream<Item = Result<B, E>>,
+    B: AsRef<[u8]>,
+    E: Into<IoError>,
+{
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            chunk: None,
+            pos: 0,
+        }
+    }
+
+    fn poll_fill_chunk(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<&[u8], IoError>> {
+        let mut this = self.project();
+
+        loop {
+            if let Some(chunk) = this.chunk.as_ref() {
+                if *this.pos < chunk.as_ref().len() {
+                    break;
+                }
+            }
+
+            match ready!(this.stream.as_mut().poll_next(cx)) {
+                Some(Ok(chunk)) => {
+                    *this.chunk = Some(chunk);
+                    *this.pos = 0;
+                }
+                Some(Err(err)) => return Poll::Ready(Err(err.into())),
+                None => return Poll::Ready(Ok(&[])),
+            }
+        }
+
+        let chunk = this.chunk.as_ref().expect("Never").as_ref();
+        Poll::Ready(Ok(&chunk[*this.pos..]))
+    }
+
+    fn consume_chunk(self: Pin<&mut Self>, amt: usize) {
+        let this = self.project();
+
+        if let Some(chunk) = this.chunk.as_ref() {
+            *this.pos += amt;
+            if *this.pos >= chunk.as_ref().len() {
+                *this.chunk = None;
+                *this.pos = 0;
+            }
+        }
+    }
+}
+
+impl<S, B> StreamReader<S, B> {
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.stream
+    }
+
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut S> {
+        self.project().stream
+    }
+
+    /// The unread part of the current chunk is lost.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+//
+impl<S, B, E> AsyncBufRead for StreamReader<S, B>
+where
+    S: Stream<Item = Result<B, E>>,
+    B: AsRef<[u8]>,
+    E: Into<IoError>,
+{
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<&[u8], IoError>> {
+        self.poll_fill_chunk(cx)
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        self.consume_chunk(amt)
+    }
+}
+
+impl<S, B, E> AsyncRead for StreamReader<S, B>
+where
+    S: Stream<Item = Result<B, E>>,
+    B: AsRef<[u8]>,
+    E: Into<IoError>,
+{
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize, IoError>> {
+        let chunk = ready!(self.as_mut().poll_fill_chunk(cx))?;
+
+        let n = core::cmp::min(chunk.len(), buf.len());
+        buf[..n].copy_from_slice(&chunk[..n]);
+
+        self.consume_chunk(n);
+
+        Poll::Ready(Ok(n))
+    }
+}
+
+//
+#[cfg(feature = "tokio_io")]
+impl<S, B, E> tokio::io::AsyncBufRead for StreamReader<S, B>
+where
+    S: Stream<Item = Result<B, E>>,
+    B: AsRef<[u8]>,
+    E: Into<IoError>,
+{
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<&[u8], IoError>> {
+        self.poll_fill_chunk(cx)
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        self.consume_chunk(amt)
+    }
+}
+
+#[cfg(feature = "tokio_io")]
+impl<S, B, E> tokio::io::AsyncRead for StreamReader<S, B>
+where
+    S: Stream<Item = Result<B, E>>,
+    B: AsRef<[u8]>,
+    E: Into<IoError>,
+{
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> Poll<Result<(), IoError>> {
+        let chunk = ready!(self.as_mut().poll_fill_chunk(cx))?;
+
+        let n = core::cmp::min(chunk.len(), buf.remaining());
+        buf.put_slice(&chunk[..n]);
+
+        self.consume_chunk(n);
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::ErrorKind as IoErrorKind;
+
+    use futures_util::{
+        io::{AsyncBufReadExt as _, AsyncReadExt as _},
+        stream,
+    };
+
+    #[test]
+    fn simple() -> Result<(), Box<dyn std::error::Error>> {
+        futures_executor::block_on(async {
+            let st = stream::iter(vec![
+                Ok::<_, IoError>(&b"ab"[..]),
+                Ok(b""),
+                Ok(b"c\nde"),
+                Ok(b"f"),
+            ]);
+            let mut r = StreamReader::new(st);
+
+            let mut line = String::new();
+            r.read_line(&mut line).await?;
+            assert_eq!(line, "abc\n");
+
+            let mut buf = vec![];
+            r.read_to_end(&mut buf).await?;
+            assert_eq!(buf, b"def");
+
+            let st = stream::iter(vec![
+                Ok(vec![1]),
+                Err(IoError::new(IoErrorKind::TimedOut, "foo")),
+            ]);
+            let mut r = StreamReader::new(st);
+            let mut buf = vec![];
+            let err = r.read_to_end(&mut buf).await.err().ok_or("should fail")?;
+            assert_eq!(err.kind(), IoErrorKind::TimedOut);
+            assert_eq!(buf, &[1]);
+
+            Ok(())
+        })
+    }
+
+    #[cfg(feature = "tokio_io")]
+    #[test]
+    fn test_tokio_io() -> Result<(), Box<dyn std::error::Error>> {
+        use tokio::io::{AsyncBufRead, AsyncRead, ReadBuf};
+
+        futures_executor::block_on(async {
+            let st = stream::iter(vec![Ok::<_, IoError>(&b"ab"[..]), Ok(b"cd")]);
+            let mut r = StreamReader::new(st);
+
+            let chunk = futures_util::future::poll_fn(|cx| {
+                AsyncBufRead::poll_fill_buf(Pin::new(&mut r), cx).map_ok(|x| x.to_vec())
+            })
+            .await?;
+            assert_eq!(chunk, b"ab");
+            AsyncBufRead::consume(Pin::new(&mut r), 1);
+
+            let mut buf = [0; 8];
+            let mut read_buf = ReadBuf::new(&mut buf);
+            futures_util::future::poll_fn(|cx| {
+                AsyncRead::poll_read(Pin::new(&mut r), cx, &mut read_buf)
+            })
+            .await?;
+            assert_eq!(read_buf.filled(), b"b");
+
+            read_buf.clear();
+            futures_util::future::poll_fn(|cx| {
+                AsyncRead::poll_read(Pin::new(&mut r), cx, &mut read_buf)
+            })
+            .await?;
+            assert_eq!(read_buf.filled(), b"cd");
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,221 @@
+use core::{
+    fmt,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+use std::{
+    collections::VecDeque,
+    io::Error as IoError,
+    sync::{Arc, Mutex},
+};
+
+use futures_util::{stream::FusedStream, Stream};
+
+//
+/// Split a reader stream, e.g. [`crate::ReaderStream`], into `n` streams of the same items.
+///
+/// At most `capacity` items are buffered, so the slowest stream holds the others back.
+/// A dropped stream no longer holds anything back.
+pub fn tee<St, B>(inner: St, n: usize, capacity: usize) -> Vec<Tee<St, B>>
+where
+    St: Stream<Item = Result<B, IoError>>,
+    B: Clone,
+{
+    let shared = Arc::new(Mutex::new(Shared {
+        inner: Box::pin(inner),
+        buf: VecDeque::new(),
+        base: 0,
+        capacity: capacity.max(1),
+        done: false,
+        positions: vec![Some(0); n],
+        wakers: Vec::new(),
+    }));
+
+    (0..n)
+        .map(|id| Tee {
+            shared: shared.clone(),
+            id,
+        })
+        .collect()
+}
+
+//
+struct Shared<St, B> {
+    inner: Pin<Box<St>>,
+    // Errors are shared, every stream yields an error wrapping it.
+    buf: VecDeque<Result<B, Arc<IoError>>>,
+    // The position of buf[0].
+    base: u64,
+    capacity: usize,
+    done: bool,
+    // `None` once dropped.
+    positions: Vec<Option<u64>>,
+    wakers: Vec<Waker>,
+}
+
+impl<St, B> Shared<St, B> {
+    fn wake_all(&mut self) {
+        for waker in self.wakers.drain(..) {
+            waker.wake();
+        }
+    }
+
+    fn register(&mut self, cx: &Context<'_>) {
+        if !self.wakers.iter().any(|x| x.will_wake(cx.waker())) {
+            self.wakers.push(cx.waker().clone());
+        }
+    }
+
+    // Drops the items seen by every stream.
+    fn trim(&mut self) {
+        let min = self.positions.iter().flatten().min().copied();
+        let mut trimmed = false;
+        while !self.buf.is_empty() && min.is_some_and(|x| x > self.base) {
+            self.buf.pop_front();
+            self.base += 1;
+            trimmed = true;
+        }
+        if trimmed {
+            self.wake_all();
+        }
+    }
+}
+
+//
+/// One of the streams of [`tee`].
+#[must_use = "streams do nothing unless polled"]
+pub struct Tee<St, B> {
+    shared: Arc<Mutex<Shared<St, B>>>,
+    id: usize,
+}
+
+impl<St, B> fmt::Debug for Tee<St, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tee").field("id", &self.id).finish()
+    }
+}
+
+impl<St, B> Drop for Tee<St, B> {
+    fn drop(&mut self) {
+        let mut shared = self.shared.lock().expect("Never poisoned");
+        shared.positions[self.id] = None;
+        shared.trim();
+        // The dropped stream may be the one the inner stream wakes.
+        shared.wake_all();
+    }
+}
+
+//
+impl<St, B> FusedStream for Tee<St, B>
+where
+    St: Stream<Item = Result<B, IoError>>,
+    B: Clone,
+{
+    fn is_terminated(&self) -> bool {
+        let shared = self.shared.lock().expect("Never poisoned");
+        shared.done && shared.positions[self.id] == Some(shared.base + shared.buf.len() as u64)
+    }
+}
+
+//
+impl<St, B> Stream for Tee<St, B>
+where
+    St: Stream<Item = Result<B, IoError>>,
+    B: Clone,
+{
+    type Item = Result<B, IoError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut shared = self.shared.lock().expect("Never poisoned");
+        let shared = &mut *shared;
+
+        let pos = shared.positions[self.id].expect("Never");
+        let end = shared.base + shared.buf.len() as u64;
+
+        if pos == end {
+            if shared.done {
+                return Poll::Ready(None);
+            }
+            if shared.buf.len() >= shared.capacity {
+                shared.register(cx);
+                return Poll::Pending;
+            }
+
+            match shared.inner.as_mut().poll_next(cx) {
+                Poll::Ready(Some(item)) => {
+                    shared.buf.push_back(item.map_err(Arc::new));
+                    shared.wake_all();
+                }
+                Poll::Ready(None) => {
+                    shared.done = true;
+                    shared.wake_all();
+                    return Poll::Ready(None);
+                }
+                Poll::Pending => {
+                    shared.register(cx);
+                    return Poll::Pending;
+                }
+            }
+        }
+
+        let item = match &shared.buf[(pos - shared.base) as usize] {
+            Ok(chunk) => Ok(chunk.clone()),
+            Err(err) => Err(IoError::new(err.kind(), err.clone())),
+        };
+        shared.positions[self.id] = Some(pos + 1);
+        shared.trim();
+
+        Poll::Ready(Some(item))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures_util::{future::join_all, io::Cursor, FutureExt as _, StreamExt as _};
+
+    use crate::ReaderStream;
+
+    #[test]
+    fn simple() -> Result<(), Box<dyn std::error::Error>> {
+        futures_executor::block_on(async {
+            let r = Cursor::new(b"1234567890");
+            let tees = tee(ReaderStream::with_capacity(r, 2), 3, 2);
+
+            let outputs = join_all(tees.into_iter().map(|st| st.collect::<Vec<_>>())).await;
+            for output in outputs {
+                let chunks = output.into_iter().collect::<Result<Vec<_>, _>>()?;
+                assert_eq!(chunks, [b"12", b"34", b"56", b"78", b"90"]);
+            }
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_backpressure_and_drop() -> Result<(), Box<dyn std::error::Error>> {
+        futures_executor::block_on(async {
+            let r = Cursor::new(b"1234567890");
+            let mut tees = tee(ReaderStream::with_capacity(r, 2), 2, 2);
+            let mut slow = tees.pop().ok_or("tees is empty")?;
+            let mut fast = tees.pop().ok_or("tees is empty")?;
+
+            assert_eq!(fast.next().await.ok_or("fast.next() is_none")??, b"12");
+            assert_eq!(fast.next().await.ok_or("fast.next() is_none")??, b"34");
+            // The buffer is full until the slow one catches up.
+            assert!(fast.next().now_or_never().is_none());
+
+            assert_eq!(slow.next().await.ok_or("slow.next() is_none")??, b"12");
+            assert_eq!(fast.next().await.ok_or("fast.next() is_none")??, b"56");
+            assert!(fast.next().now_or_never().is_none());
+
+            drop(slow);
+            let rest = fast.by_ref().collect::<Vec<_>>().await;
+            assert_eq!(rest.len(), 2);
+            assert!(fast.is_terminated());
+
+            Ok(())
+        })
+    }
+}
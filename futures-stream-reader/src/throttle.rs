@@ -0,0 +1,188 @@
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use std::{io::Error as IoError, time::Instant};
+
+use futures_util::{ready, stream::FusedStream, Stream};
+use pin_project_lite::pin_project;
+
+use crate::timer::Timer;
+
+//
+const NANOS_PER_SEC: i128 = 1_000_000_000;
+
+pin_project! {
+    /// Stream of the chunks of a reader stream, e.g. [`crate::ReaderStream`], capped by a token bucket.
+    ///
+    /// The bucket holds up to `burst` bytes and refills at `bytes_per_sec`. Once a chunk overdraws it,
+    /// the next read waits until it is refilled to zero.
+    #[must_use = "streams do nothing unless polled"]
+    pub struct Throttle<St, T: Timer> {
+        #[pin]
+        inner: St,
+        timer: T,
+        #[pin]
+        sleep: Option<T::Sleep>,
+        bytes_per_sec: u64,
+        // In bytes * NANOS_PER_SEC to keep the refill exact.
+        tokens: i128,
+        max_tokens: i128,
+        last: Instant,
+    }
+}
+
+impl<St, T: Timer> Throttle<St, T> {
+    pub fn new(inner: St, timer: T, bytes_per_sec: u64, burst: u64) -> Self {
+        assert!(bytes_per_sec > 0, "bytes_per_sec must be greater than 0");
+
+        let max_tokens = i128::from(burst) * NANOS_PER_SEC;
+        let last = timer.now();
+
+        Self {
+            inner,
+            timer,
+            sleep: None,
+            bytes_per_sec,
+            tokens: max_tokens,
+            max_tokens,
+            last,
+        }
+    }
+
+    pub fn get_ref(&self) -> &St {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut St {
+        &mut self.inner
+    }
+
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut St> {
+        self.project().inner
+    }
+
+    pub fn into_inner(self) -> St {
+        self.inner
+    }
+}
+
+//
+impl<St, T, B> FusedStream for Throttle<St, T>
+where
+    St: FusedStream<Item = Result<B, IoError>>,
+    T: Timer,
+    B: AsRef<[u8]>,
+{
+    fn is_terminated(&self) -> bool {
+        self.inner.is_terminated()
+    }
+}
+
+//
+impl<St, T, B> Stream for Throttle<St, T>
+where
+    St: Stream<Item = Result<B, IoError>>,
+    T: Timer,
+    B: AsRef<[u8]>,
+{
+    type Item = Result<B, IoError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        loop {
+            if let Some(sleep) = this.sleep.as_mut().as_pin_mut() {
+                ready!(sleep.poll(cx));
+                this.sleep.set(None);
+            }
+
+            let now = this.timer.now();
+            let elapsed = now.saturating_duration_since(*this.last).as_nanos();
+            *this.last = now;
+            *this.tokens = core::cmp::min(
+                *this.tokens + elapsed as i128 * i128::from(*this.bytes_per_sec),
+                *this.max_tokens,
+            );
+
+            if *this.tokens < 0 {
+                let rate = i128::from(*this.bytes_per_sec);
+                let wait = (-*this.tokens + rate - 1) / rate;
+                this.sleep
+                    .set(Some(this.timer.sleep(Duration::from_nanos(wait as u64))));
+                continue;
+            }
+
+            let item = ready!(this.inner.as_mut().poll_next(cx));
+            if let Some(Ok(chunk)) = &item {
+                *this.tokens -= chunk.as_ref().len() as i128 * NANOS_PER_SEC;
+            }
+            return Poll::Ready(item);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures_util::{io::Cursor, StreamExt as _};
+
+    use crate::{timer::MockTimer, ReaderStream};
+
+    #[test]
+    fn simple() -> Result<(), Box<dyn std::error::Error>> {
+        futures_executor::block_on(async {
+            let timer = MockTimer::new();
+
+            let r = Cursor::new(vec![0; 10_000]);
+            let st = Throttle::new(
+                ReaderStream::with_capacity(r, 1000),
+                timer.clone(),
+                1000,
+                1000,
+            );
+            let chunks = st
+                .collect::<Vec<_>>()
+                .await
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(chunks.len(), 10);
+            // The first chunk is covered by the burst.
+            assert_eq!(timer.elapsed(), Duration::from_secs(9));
+
+            let timer = MockTimer::new();
+
+            let r = Cursor::new(vec![0; 10_000]);
+            let st = Throttle::new(
+                ReaderStream::with_capacity(r, 3000),
+                timer.clone(),
+                2000,
+                5000,
+            );
+            let n = st
+                .fold(
+                    0,
+                    |n, chunk| async move { n + chunk.map_or(0, |x| x.len()) },
+                )
+                .await;
+            assert_eq!(n, 10_000);
+            // 5000 from the burst, 5000 at 2000/s.
+            assert_eq!(timer.elapsed(), Duration::from_millis(2500));
+
+            // A sleep function on the system clock.
+            let r = Cursor::new(vec![0; 10]);
+            let st = Throttle::new(
+                ReaderStream::with_capacity(r, 3),
+                |_dur| async {},
+                1_000_000,
+                1,
+            );
+            assert_eq!(st.count().await, 4);
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,242 @@
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use std::{
+    io::{Error as IoError, ErrorKind as IoErrorKind},
+    time::Instant,
+};
+
+use futures_util::{stream::FusedStream, Stream};
+use pin_project_lite::pin_project;
+
+use crate::timer::Timer;
+
+//
+pin_project! {
+    /// Stream of the chunks of a reader stream, e.g. [`crate::ReaderStream`], with a per-read and a total timeout.
+    ///
+    /// A timeout is yielded as an [`IoErrorKind::TimedOut`] error, and by default ends the stream,
+    /// see [`Timeout::set_terminate_on_timeout`].
+    #[must_use = "streams do nothing unless polled"]
+    pub struct Timeout<St, T: Timer> {
+        #[pin]
+        inner: St,
+        timer: T,
+        read_timeout: Option<Duration>,
+        total_timeout: Option<Duration>,
+        terminate_on_timeout: bool,
+        #[pin]
+        read_sleep: Option<T::Sleep>,
+        #[pin]
+        total_sleep: Option<T::Sleep>,
+        deadline: Option<Instant>,
+        done: bool,
+    }
+}
+
+impl<St, T: Timer> Timeout<St, T> {
+    pub fn new(inner: St, timer: T) -> Self {
+        Self {
+            inner,
+            timer,
+            read_timeout: None,
+            total_timeout: None,
+            terminate_on_timeout: true,
+            read_sleep: None,
+            total_sleep: None,
+            deadline: None,
+            done: false,
+        }
+    }
+
+    /// The longest wait for the next chunk.
+    pub fn set_read_timeout(&mut self, read_timeout: Option<Duration>) {
+        self.read_timeout = read_timeout;
+    }
+
+    /// The longest time from the first poll, it fires once.
+    pub fn set_total_timeout(&mut self, total_timeout: Option<Duration>) {
+        self.total_timeout = total_timeout;
+    }
+
+    pub fn set_terminate_on_timeout(&mut self, terminate_on_timeout: bool) {
+        self.terminate_on_timeout = terminate_on_timeout;
+    }
+
+    pub fn get_ref(&self) -> &St {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut St {
+        &mut self.inner
+    }
+
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut St> {
+        self.project().inner
+    }
+
+    pub fn into_inner(self) -> St {
+        self.inner
+    }
+}
+
+//
+impl<St, T, B> FusedStream for Timeout<St, T>
+where
+    St: Stream<Item = Result<B, IoError>>,
+    T: Timer,
+{
+    fn is_terminated(&self) -> bool {
+        self.done
+    }
+}
+
+//
+impl<St, T, B> Stream for Timeout<St, T>
+where
+    St: Stream<Item = Result<B, IoError>>,
+    T: Timer,
+{
+    type Item = Result<B, IoError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        if *this.done {
+            return Poll::Ready(None);
+        }
+
+        if this.deadline.is_none() {
+            if let Some(total_timeout) = this.total_timeout.take() {
+                *this.deadline = Some(this.timer.now() + total_timeout);
+            }
+        }
+
+        // The sleeps are only created once the inner stream is pending, see MockTimer.
+        let mut total_timed_out = this
+            .deadline
+            .is_some_and(|deadline| this.timer.now() >= deadline);
+
+        if !total_timed_out {
+            match this.inner.poll_next(cx) {
+                Poll::Ready(item) => {
+                    this.read_sleep.set(None);
+                    if item.is_none() {
+                        *this.done = true;
+                    }
+                    return Poll::Ready(item);
+                }
+                Poll::Pending => {}
+            }
+
+            if let Some(deadline) = *this.deadline {
+                if this.total_sleep.is_none() {
+                    let dur = deadline.saturating_duration_since(this.timer.now());
+                    this.total_sleep.set(Some(this.timer.sleep(dur)));
+                }
+                if let Some(sleep) = this.total_sleep.as_mut().as_pin_mut() {
+                    total_timed_out = sleep.poll(cx).is_ready();
+                }
+            }
+        }
+
+        if total_timed_out {
+            *this.deadline = None;
+            this.total_sleep.set(None);
+            *this.done = *this.terminate_on_timeout;
+            return Poll::Ready(Some(Err(IoError::new(
+                IoErrorKind::TimedOut,
+                "total timed out",
+            ))));
+        }
+
+        if let Some(read_timeout) = *this.read_timeout {
+            if this.read_sleep.is_none() {
+                this.read_sleep.set(Some(this.timer.sleep(read_timeout)));
+            }
+            if let Some(sleep) = this.read_sleep.as_mut().as_pin_mut() {
+                if sleep.poll(cx).is_ready() {
+                    this.read_sleep.set(None);
+                    *this.done = *this.terminate_on_timeout;
+                    return Poll::Ready(Some(Err(IoError::new(
+                        IoErrorKind::TimedOut,
+                        format!("read timed out, dur:{read_timeout:?}"),
+                    ))));
+                }
+            }
+        }
+
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures_util::{io::Cursor, stream, StreamExt as _};
+
+    use crate::{timer::MockTimer, ReaderStream};
+
+    #[test]
+    fn test_read_timeout() -> Result<(), Box<dyn std::error::Error>> {
+        futures_executor::block_on(async {
+            let timer = MockTimer::new();
+
+            let inner = stream::iter([Ok(b"12".to_vec())]).chain(stream::pending());
+            let mut st = Timeout::new(inner, timer.clone());
+            st.set_read_timeout(Some(Duration::from_secs(3)));
+            st.set_terminate_on_timeout(false);
+
+            assert_eq!(st.next().await.ok_or("st.next() is_none")??, b"12");
+            for i in 1..=2 {
+                match st.next().await {
+                    Some(Err(err)) => {
+                        assert_eq!(err.kind(), IoErrorKind::TimedOut);
+                        assert_eq!(err.to_string(), "read timed out, dur:3s");
+                    }
+                    x => panic!("{x:?}"),
+                }
+                assert_eq!(timer.elapsed(), Duration::from_secs(3 * i));
+            }
+
+            //
+            let mut st = Timeout::new(stream::pending::<Result<Vec<u8>, _>>(), timer.clone());
+            st.set_read_timeout(Some(Duration::from_secs(3)));
+            assert!(st.next().await.ok_or("st.next() is_none")?.is_err());
+            assert!(st.next().await.is_none());
+            assert!(st.is_terminated());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_total_timeout() -> Result<(), Box<dyn std::error::Error>> {
+        futures_executor::block_on(async {
+            let timer = MockTimer::new();
+
+            let r = Cursor::new(b"1234567890");
+            let mut st = Timeout::new(ReaderStream::with_capacity(r, 2), timer.clone());
+            st.set_total_timeout(Some(Duration::from_secs(5)));
+
+            assert_eq!(st.next().await.ok_or("st.next() is_none")??, b"12");
+            timer.advance(Duration::from_secs(4));
+            assert_eq!(st.next().await.ok_or("st.next() is_none")??, b"34");
+            timer.advance(Duration::from_secs(1));
+            match st.next().await {
+                Some(Err(err)) => {
+                    assert_eq!(err.kind(), IoErrorKind::TimedOut);
+                    assert_eq!(err.to_string(), "total timed out");
+                }
+                x => panic!("{x:?}"),
+            }
+            assert!(st.next().await.is_none());
+
+            Ok(())
+        })
+    }
+}
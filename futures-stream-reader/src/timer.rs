@@ -0,0 +1,78 @@
+use core::{future::Future, time::Duration};
+use std::{
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use futures_util::future::{ready, Ready};
+
+//
+/// Runtime agnostic clock and sleep, e.g. backed by `tokio::time` or `async_io::Timer`.
+pub trait Timer {
+    type Sleep: Future<Output = ()>;
+
+    fn now(&self) -> Instant;
+
+    fn sleep(&self, dur: Duration) -> Self::Sleep;
+}
+
+/// Any sleep function is a [`Timer`] on the system clock, e.g. `tokio::time::sleep` or `|dur| async { async_io::Timer::after(dur).await; }`.
+impl<F, Fut> Timer for F
+where
+    F: Fn(Duration) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    type Sleep = Fut;
+
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, dur: Duration) -> Self::Sleep {
+        self(dur)
+    }
+}
+
+//
+/// [`Timer`] with virtual time for deterministic tests, every sleep completes at once and advances the clock.
+#[derive(Debug, Clone)]
+pub struct MockTimer {
+    start: Instant,
+    elapsed: Arc<Mutex<Duration>>,
+}
+
+impl Default for MockTimer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockTimer {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            elapsed: Default::default(),
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        *self.elapsed.lock().expect("Never poisoned")
+    }
+
+    pub fn advance(&self, dur: Duration) {
+        *self.elapsed.lock().expect("Never poisoned") += dur;
+    }
+}
+
+impl Timer for MockTimer {
+    type Sleep = Ready<()>;
+
+    fn now(&self) -> Instant {
+        self.start + self.elapsed()
+    }
+
+    fn sleep(&self, dur: Duration) -> Self::Sleep {
+        self.advance(dur);
+        ready(())
+    }
+}
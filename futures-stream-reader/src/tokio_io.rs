@@ -0,0 +1,202 @@
+//! The same streams for [`tokio::io::AsyncRead`], reading into uninitialized memory.
+
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::io::Error as IoError;
+
+use futures_util::{ready, stream::FusedStream, Stream};
+use pin_project_lite::pin_project;
+use tokio::io::{AsyncRead, ReadBuf};
+
+use crate::{
+    on_error::OnError,
+    read_state::{ReadRet, ReadState},
+    read_strategy::ReadStrategy,
+    DEFAULT_CAPACITY,
+};
+
+//
+pub fn reader<R: AsyncRead + Unpin + Send + 'static>(
+    reader: R,
+) -> Pin<Box<dyn Stream<Item = Result<Vec<u8>, IoError>> + Send + 'static>> {
+    reader_with_capacity(reader, DEFAULT_CAPACITY)
+}
+
+/// Errors are yielded and reading continues, use [`ReaderStream`] to control that.
+pub fn reader_with_capacity<R: AsyncRead + Unpin + Send + 'static>(
+    reader: R,
+    capacity: usize,
+) -> Pin<Box<dyn Stream<Item = Result<Vec<u8>, IoError>> + Send + 'static>> {
+    let mut st = ReaderStream::with_capacity(reader, capacity);
+    st.set_on_error(OnError::Continue);
+    Box::pin(st)
+}
+
+pub fn reader_ref<'a, R: AsyncRead + Unpin + Send>(
+    reader: &'a mut R,
+) -> Pin<Box<dyn Stream<Item = Result<Vec<u8>, IoError>> + Send + 'a>> {
+    reader_ref_with_capacity(reader, DEFAULT_CAPACITY)
+}
+
+/// Errors are yielded and reading continues, use [`ReaderStream`] to control that.
+pub fn reader_ref_with_capacity<'a, R: AsyncRead + Unpin + Send>(
+    reader: &'a mut R,
+    capacity: usize,
+) -> Pin<Box<dyn Stream<Item = Result<Vec<u8>, IoError>> + Send + 'a>> {
+    let mut st = ReaderStream::with_capacity(reader, capacity);
+    st.set_on_error(OnError::Continue);
+    Box::pin(st)
+}
+
+//
+pin_project! {
+    /// Stream of the byte chunks read from a [`tokio::io::AsyncRead`], it ends at EOF.
+    ///
+    /// By default it also ends after the first error, see [`ReaderStream::set_on_error`].
+    #[derive(Debug)]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct ReaderStream<R> {
+        #[pin]
+        reader: R,
+        buf: Vec<u8>,
+        state: ReadState,
+    }
+}
+
+impl<R: AsyncRead> ReaderStream<R> {
+    pub fn new(reader: R) -> Self {
+        Self::with_capacity(reader, DEFAULT_CAPACITY)
+    }
+
+    pub fn with_capacity(reader: R, capacity: usize) -> Self {
+        Self {
+            reader,
+            buf: Vec::with_capacity(capacity),
+            state: ReadState::new(ReadStrategy::Exact(capacity)),
+        }
+    }
+
+    /// The read size starts at `min`, doubles up to `max` while reads fill it,
+    /// and halves down to `min` while reads are small.
+    pub fn with_adaptive_capacity(reader: R, min: usize, max: usize) -> Self {
+        let strategy = ReadStrategy::adaptive(min, max);
+        Self {
+            reader,
+            buf: Vec::with_capacity(strategy.next()),
+            state: ReadState::new(strategy),
+        }
+    }
+}
+
+impl<R> ReaderStream<R> {
+    pub fn set_on_error(&mut self, on_error: OnError) {
+        self.state.set_on_error(on_error);
+    }
+
+    /// Read at most `limit` more bytes, then end as at EOF.
+    pub fn set_limit(&mut self, limit: Option<u64>) {
+        self.state.set_limit(limit);
+    }
+
+    /// The size of the next read.
+    pub fn read_size(&self) -> usize {
+        self.state.read_size()
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut R> {
+        self.project().reader
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+//
+impl<R: AsyncRead> FusedStream for ReaderStream<R> {
+    fn is_terminated(&self) -> bool {
+        self.state.is_done()
+    }
+}
+
+//
+impl<R: AsyncRead> Stream for ReaderStream<R> {
+    type Item = Result<Vec<u8>, IoError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        loop {
+            let Some(len) = this.state.begin() else {
+                return Poll::Ready(None);
+            };
+
+            let read_size = this.state.read_size();
+            if this.buf.capacity() < read_size {
+                this.buf.reserve_exact(read_size);
+            } else if this.buf.capacity() > read_size {
+                this.buf.shrink_to(read_size);
+            }
+
+            let mut read_buf = ReadBuf::uninit(&mut this.buf.spare_capacity_mut()[..len]);
+            let ret = ready!(this.reader.as_mut().poll_read(cx, &mut read_buf));
+            return match this.state.finish(ret.map(|()| read_buf.filled().len())) {
+                ReadRet::Chunk(n) => Poll::Ready(Some(Ok(read_buf.filled()[..n].to_vec()))),
+                ReadRet::Eof => Poll::Ready(None),
+                ReadRet::Err(err) => Poll::Ready(Some(Err(err))),
+                ReadRet::Retry => continue,
+                ReadRet::RetryLater => {
+                    cx.waker().wake_by_ref();
+                    Poll::Pending
+                }
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures_util::StreamExt as _;
+
+    #[test]
+    fn simple() -> Result<(), Box<dyn std::error::Error>> {
+        futures_executor::block_on(async {
+            let mut st = ReaderStream::with_capacity(&b"1234567890"[..], 4);
+            assert_eq!(st.next().await.ok_or("st.next() is_none")??, b"1234");
+            assert_eq!(st.next().await.ok_or("st.next() is_none")??, b"5678");
+            assert_eq!(st.next().await.ok_or("st.next() is_none")??, b"90");
+            assert!(st.next().await.is_none());
+            assert!(st.is_terminated());
+
+            let mut r = &b"1234567890"[..];
+            let chunks = reader_ref_with_capacity(&mut r, 3)
+                .collect::<Vec<_>>()
+                .await
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(
+                chunks,
+                vec![
+                    b"123".to_vec(),
+                    b"456".to_vec(),
+                    b"789".to_vec(),
+                    b"0".to_vec()
+                ]
+            );
+
+            Ok(())
+        })
+    }
+}
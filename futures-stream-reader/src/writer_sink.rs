@@ -0,0 +1,137 @@
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::io::{Error as IoError, ErrorKind as IoErrorKind};
+
+use futures_util::{ready, AsyncWrite, Sink};
+use pin_project_lite::pin_project;
+
+use crate::DEFAULT_CAPACITY;
+
+//
+pin_project! {
+    /// Sink of byte chunks into an [`AsyncWrite`], the inverse of [`crate::SinkWriter`].
+    ///
+    /// Chunks are buffered until `capacity` bytes are pending, `flush` writes them all.
+    #[derive(Debug)]
+    pub struct WriterSink<W> {
+        #[pin]
+        writer: W,
+        buf: Vec<u8>,
+        n_write: usize,
+        capacity: usize,
+    }
+}
+
+impl<W: AsyncWrite> WriterSink<W> {
+    pub fn new(writer: W) -> Self {
+        Self::with_capacity(writer, DEFAULT_CAPACITY)
+    }
+
+    pub fn with_capacity(writer: W, capacity: usize) -> Self {
+        Self {
+            writer,
+            buf: Vec::with_capacity(capacity),
+            n_write: 0,
+            capacity,
+        }
+    }
+
+    fn poll_write_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), IoError>> {
+        let mut this = self.project();
+
+        while !this.buf[*this.n_write..].is_empty() {
+            let n = ready!(this
+                .writer
+                .as_mut()
+                .poll_write(cx, &this.buf[*this.n_write..]))?;
+            *this.n_write += n;
+
+            if n == 0 {
+                return Poll::Ready(Err(IoErrorKind::WriteZero.into()));
+            }
+        }
+        this.buf.clear();
+        *this.n_write = 0;
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<W> WriterSink<W> {
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut W> {
+        self.project().writer
+    }
+
+    /// Chunks sent but not yet written are lost, call `close` or `flush` first.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+//
+impl<T: AsRef<[u8]>, W: AsyncWrite> Sink<T> for WriterSink<W> {
+    type Error = IoError;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        if self.buf.len() >= self.capacity {
+            self.poll_write_buf(cx)
+        } else {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
+        let this = self.project();
+
+        this.buf.extend_from_slice(item.as_ref());
+
+        Ok(())
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        ready!(self.as_mut().poll_write_buf(cx))?;
+
+        let this = self.project();
+        this.writer.poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        ready!(self.as_mut().poll_write_buf(cx))?;
+
+        let this = self.project();
+        this.writer.poll_close(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures_util::{io::Cursor, stream, SinkExt, StreamExt as _};
+
+    #[test]
+    fn simple() -> Result<(), Box<dyn std::error::Error>> {
+        futures_executor::block_on(async {
+            let mut sink = WriterSink::with_capacity(Cursor::new(vec![]), 4);
+            sink.send(b"abc").await?;
+            sink.feed(vec![b'd']).await?;
+            sink.send_all(&mut stream::iter(["ef", "", "g"]).map(Ok))
+                .await?;
+            SinkExt::<&[u8]>::close(&mut sink).await?;
+
+            assert_eq!(sink.into_inner().get_ref(), b"abcdefg");
+
+            Ok(())
+        })
+    }
+}
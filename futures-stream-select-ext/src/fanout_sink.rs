@@ -0,0 +1,149 @@
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_util::Sink;
+use pin_project_lite::pin_project;
+
+//
+pin_project! {
+    /// Sink for the [`fanout_sink()`] function. See function docs for details.
+    #[derive(Debug)]
+    #[must_use = "sinks do nothing unless polled"]
+    pub struct FanoutSink<Si1, Si2> {
+        #[pin]
+        sink1: Si1,
+        #[pin]
+        sink2: Si2,
+        sink1_closed: bool,
+        sink2_closed: bool,
+    }
+}
+
+/// Send a clone of every item to both `sink1` and `sink2`.
+///
+/// It is ready once both sinks are ready. Flushing and closing go to both sinks, a sink closed first is not polled again.
+pub fn fanout_sink<Si1, Si2, Item>(sink1: Si1, sink2: Si2) -> FanoutSink<Si1, Si2>
+where
+    Si1: Sink<Item>,
+    Si2: Sink<Item, Error = Si1::Error>,
+    Item: Clone,
+{
+    FanoutSink {
+        sink1,
+        sink2,
+        sink1_closed: false,
+        sink2_closed: false,
+    }
+}
+
+//
+impl<Si1, Si2> FanoutSink<Si1, Si2> {
+    pub fn get_ref(&self) -> (&Si1, &Si2) {
+        (&self.sink1, &self.sink2)
+    }
+
+    pub fn get_mut(&mut self) -> (&mut Si1, &mut Si2) {
+        (&mut self.sink1, &mut self.sink2)
+    }
+
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> (Pin<&mut Si1>, Pin<&mut Si2>) {
+        let this = self.project();
+        (this.sink1, this.sink2)
+    }
+
+    pub fn into_inner(self) -> (Si1, Si2) {
+        (self.sink1, self.sink2)
+    }
+}
+
+//
+impl<Si1, Si2, Item> Sink<Item> for FanoutSink<Si1, Si2>
+where
+    Si1: Sink<Item>,
+    Si2: Sink<Item, Error = Si1::Error>,
+    Item: Clone,
+{
+    type Error = Si1::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let this = self.project();
+
+        let sink1_ready = this.sink1.poll_ready(cx)?.is_ready();
+        let sink2_ready = this.sink2.poll_ready(cx)?.is_ready();
+
+        if sink1_ready && sink2_ready {
+            Poll::Ready(Ok(()))
+        } else {
+            Poll::Pending
+        }
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Item) -> Result<(), Self::Error> {
+        let this = self.project();
+
+        this.sink1.start_send(item.clone())?;
+        this.sink2.start_send(item)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let this = self.project();
+
+        let sink1_ready = *this.sink1_closed || this.sink1.poll_flush(cx)?.is_ready();
+        let sink2_ready = *this.sink2_closed || this.sink2.poll_flush(cx)?.is_ready();
+
+        if sink1_ready && sink2_ready {
+            Poll::Ready(Ok(()))
+        } else {
+            Poll::Pending
+        }
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let this = self.project();
+
+        if !*this.sink1_closed {
+            *this.sink1_closed = this.sink1.poll_close(cx)?.is_ready();
+        }
+        if !*this.sink2_closed {
+            *this.sink2_closed = this.sink2.poll_close(cx)?.is_ready();
+        }
+
+        if *this.sink1_closed && *this.sink2_closed {
+            Poll::Ready(Ok(()))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures_util::{stream, SinkExt as _, StreamExt as _};
+
+    use crate::test_sink::TestSink;
+
+    #[test]
+    fn simple() -> Result<(), ()> {
+        futures_executor::block_on(async {
+            let mut sink1 = TestSink {
+                close_pending: 3,
+                ..Default::default()
+            };
+            let mut sink2 = TestSink::default();
+
+            let mut sink = fanout_sink(&mut sink1, &mut sink2);
+            sink.send_all(&mut stream::iter(1..=3).map(Ok)).await?;
+            sink.close().await?;
+
+            assert_eq!(sink1.items(), [1, 2, 3]);
+            assert_eq!(sink2.items(), [1, 2, 3]);
+            assert!(sink1.closed && sink2.closed);
+
+            Ok(())
+        })
+    }
+}
@@ -3,14 +3,30 @@
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
+#[cfg(feature = "sink")]
+mod fanout_sink;
+#[cfg(feature = "sink")]
+mod route_sink;
 #[cfg(feature = "alloc")]
 mod select_until_left_is_done;
 #[cfg(feature = "alloc")]
 mod select_until_left_is_done_with_strategy;
+#[cfg(feature = "alloc")]
+mod select_until_primary_is_done;
+#[cfg(all(test, feature = "sink"))]
+mod test_sink;
 
+#[cfg(feature = "sink")]
+pub use self::fanout_sink::{fanout_sink, FanoutSink};
+#[cfg(feature = "sink")]
+pub use self::route_sink::{route_sink_with_strategy, RouteSink};
 #[cfg(feature = "alloc")]
 pub use self::select_until_left_is_done::{select_until_left_is_done, SelectUntilLeftIsDone};
 #[cfg(feature = "alloc")]
 pub use self::select_until_left_is_done_with_strategy::{
     select_until_left_is_done_with_strategy, SelectUntilLeftIsDoneWithStrategy,
 };
+#[cfg(feature = "alloc")]
+pub use self::select_until_primary_is_done::{
+    select_until_primary_is_done, SelectUntilPrimaryIsDone,
+};
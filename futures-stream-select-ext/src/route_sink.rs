@@ -0,0 +1,195 @@
+use core::{
+    fmt,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_util::{ready, stream::PollNext, Sink};
+use pin_project_lite::pin_project;
+
+//
+pin_project! {
+    /// Sink for the [`route_sink_with_strategy()`] function. See function docs for details.
+    #[must_use = "sinks do nothing unless polled"]
+    pub struct RouteSink<Si1, Si2, Item, Clos> {
+        #[pin]
+        sink1: Si1,
+        #[pin]
+        sink2: Si2,
+        which: Clos,
+        pending: Option<(PollNext, Item)>,
+        sink1_closed: bool,
+        sink2_closed: bool,
+    }
+}
+
+/// Send every item to `sink1` or `sink2`, as chosen by `which`.
+///
+/// An item is held until the chosen sink is ready, so a slow sink only holds back the items routed to it
+/// once the next item is sent. Flushing and closing go to both sinks, a sink closed first is not polled again.
+pub fn route_sink_with_strategy<Si1, Si2, Item, Clos>(
+    sink1: Si1,
+    sink2: Si2,
+    which: Clos,
+) -> RouteSink<Si1, Si2, Item, Clos>
+where
+    Si1: Sink<Item>,
+    Si2: Sink<Item, Error = Si1::Error>,
+    Clos: FnMut(&Item) -> PollNext,
+{
+    RouteSink {
+        sink1,
+        sink2,
+        which,
+        pending: None,
+        sink1_closed: false,
+        sink2_closed: false,
+    }
+}
+
+//
+impl<Si1, Si2, Item, Clos> RouteSink<Si1, Si2, Item, Clos> {
+    pub fn get_ref(&self) -> (&Si1, &Si2) {
+        (&self.sink1, &self.sink2)
+    }
+
+    pub fn get_mut(&mut self) -> (&mut Si1, &mut Si2) {
+        (&mut self.sink1, &mut self.sink2)
+    }
+
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> (Pin<&mut Si1>, Pin<&mut Si2>) {
+        let this = self.project();
+        (this.sink1, this.sink2)
+    }
+
+    pub fn into_inner(self) -> (Si1, Si2) {
+        (self.sink1, self.sink2)
+    }
+}
+
+impl<Si1, Si2, Item, Clos> RouteSink<Si1, Si2, Item, Clos>
+where
+    Si1: Sink<Item>,
+    Si2: Sink<Item, Error = Si1::Error>,
+{
+    fn poll_pending(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Si1::Error>> {
+        let mut this = self.project();
+
+        match this.pending {
+            Some((PollNext::Left, _)) => ready!(this.sink1.as_mut().poll_ready(cx))?,
+            Some((PollNext::Right, _)) => ready!(this.sink2.as_mut().poll_ready(cx))?,
+            None => return Poll::Ready(Ok(())),
+        }
+
+        match this.pending.take() {
+            Some((PollNext::Left, item)) => this.sink1.start_send(item)?,
+            Some((PollNext::Right, item)) => this.sink2.start_send(item)?,
+            None => unreachable!(),
+        }
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+//
+impl<Si1, Si2, Item, Clos> Sink<Item> for RouteSink<Si1, Si2, Item, Clos>
+where
+    Si1: Sink<Item>,
+    Si2: Sink<Item, Error = Si1::Error>,
+    Clos: FnMut(&Item) -> PollNext,
+{
+    type Error = Si1::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.poll_pending(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Item) -> Result<(), Self::Error> {
+        let this = self.project();
+        debug_assert!(this.pending.is_none(), "start_send without poll_ready");
+
+        *this.pending = Some(((this.which)(&item), item));
+        Ok(())
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        ready!(self.as_mut().poll_pending(cx))?;
+        let this = self.project();
+
+        let sink1_ready = *this.sink1_closed || this.sink1.poll_flush(cx)?.is_ready();
+        let sink2_ready = *this.sink2_closed || this.sink2.poll_flush(cx)?.is_ready();
+
+        if sink1_ready && sink2_ready {
+            Poll::Ready(Ok(()))
+        } else {
+            Poll::Pending
+        }
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        ready!(self.as_mut().poll_pending(cx))?;
+        let this = self.project();
+
+        if !*this.sink1_closed {
+            *this.sink1_closed = this.sink1.poll_close(cx)?.is_ready();
+        }
+        if !*this.sink2_closed {
+            *this.sink2_closed = this.sink2.poll_close(cx)?.is_ready();
+        }
+
+        if *this.sink1_closed && *this.sink2_closed {
+            Poll::Ready(Ok(()))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+//
+impl<Si1, Si2, Item, Clos> fmt::Debug for RouteSink<Si1, Si2, Item, Clos>
+where
+    Si1: fmt::Debug,
+    Si2: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RouteSink")
+            .field("sink1", &self.sink1)
+            .field("sink2", &self.sink2)
+            .field("sink1_closed", &self.sink1_closed)
+            .field("sink2_closed", &self.sink2_closed)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures_util::{stream, SinkExt as _, StreamExt as _};
+
+    use crate::test_sink::TestSink;
+
+    #[test]
+    fn simple() -> Result<(), ()> {
+        futures_executor::block_on(async {
+            let mut even = TestSink::default();
+            let mut odd = TestSink {
+                close_pending: 2,
+                ..Default::default()
+            };
+
+            let mut sink = route_sink_with_strategy(&mut even, &mut odd, |n: &usize| match n % 2 {
+                0 => PollNext::Left,
+                _ => PollNext::Right,
+            });
+            sink.send_all(&mut stream::iter(1..=5).map(Ok)).await?;
+            sink.close().await?;
+
+            assert_eq!(even.items(), [2, 4]);
+            assert_eq!(odd.items(), [1, 3, 5]);
+            assert!(even.closed && odd.closed);
+
+            Ok(())
+        })
+    }
+}
@@ -98,7 +98,7 @@ mod tests {
     #[test]
     fn simple() {
         futures_executor::block_on(async {
-            for (range, ret) in vec![
+            for (range, ret) in [
                 (1..=1, vec![1, 0]),
                 (1..=2, vec![1, 0, 2, 0]),
                 (1..=3, vec![1, 0, 2, 0, 3, 0]),
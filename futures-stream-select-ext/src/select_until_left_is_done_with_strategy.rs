@@ -178,7 +178,7 @@ mod tests {
     #[test]
     fn test_with_round_robin() {
         futures_executor::block_on(async {
-            for (range, ret) in vec![
+            for (range, ret) in [
                 (1..=1, vec![1, 0]),
                 (1..=2, vec![1, 0, 2, 0]),
                 (1..=3, vec![1, 0, 2, 0, 3, 0]),
@@ -198,7 +198,7 @@ mod tests {
     #[test]
     fn test_with_right_right_left() {
         futures_executor::block_on(async {
-            for (range, ret) in vec![
+            for (range, ret) in [
                 (1..=1, vec![0, 0, 1, 0, 0]),
                 (1..=2, vec![0, 0, 1, 0, 0, 2, 0, 0]),
                 (1..=3, vec![0, 0, 1, 0, 0, 2, 0, 0, 3, 0, 0]),
@@ -221,7 +221,7 @@ mod tests {
     #[cfg(feature = "std")]
     #[tokio::test]
     async fn test_with_round_robin_and_right_long_sleep() {
-        for (range, ret) in vec![
+        for (range, ret) in [
             (1..=1, vec![1]),
             (1..=2, vec![1, 2]),
             (1..=3, vec![1, 2, 3]),
@@ -251,7 +251,7 @@ mod tests {
     #[cfg(feature = "std")]
     #[tokio::test]
     async fn test_with_round_robin_and_both_sleep() {
-        for (range, ret_vec) in vec![
+        for (range, ret_vec) in [
             (1..=1, vec![vec![1]]),
             (1..=2, vec![vec![1, 0, 2]]),
             (1..=3, vec![vec![1, 0, 2, 3]]),
@@ -289,7 +289,7 @@ mod tests {
     #[cfg(feature = "std")]
     #[tokio::test]
     async fn test_with_round_robin_and_both_sleep_2() {
-        for (range, ret_vec) in vec![
+        for (range, ret_vec) in [
             (1..=1, vec![vec![0, 1]]),
             (1..=2, vec![vec![0, 1, 0, 2]]),
             (1..=3, vec![vec![0, 1, 0, 2, 0, 0, 3]]),
@@ -333,7 +333,7 @@ mod tests {
     #[cfg(feature = "std")]
     #[tokio::test]
     async fn test_with_right_right_left_and_both_sleep() {
-        for (range, ret_vec) in vec![
+        for (range, ret_vec) in [
             (1..=1, vec![vec![0, 1]]),
             (1..=2, vec![vec![0, 1, 0, 0, 2]]),
             (1..=3, vec![vec![0, 1, 0, 0, 2, 3]]),
@@ -0,0 +1,210 @@
+use core::{
+    fmt,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_util::{
+    stream::{select_all, FusedStream, PollNext, SelectAll},
+    Stream, StreamExt as _,
+};
+use pin_project_lite::pin_project;
+
+//
+pin_project! {
+    /// Stream for the [`select_until_primary_is_done()`] function. See function docs for details.
+    #[must_use = "streams do nothing unless polled"]
+    pub struct SelectUntilPrimaryIsDone<St1, St2> {
+        #[pin]
+        primary: St1,
+        secondaries: SelectAll<St2>,
+        last: PollNext,
+        done: bool,
+    }
+}
+
+/// Like [`crate::select_until_left_is_done()`], with any number of secondary streams.
+///
+/// The primary stream and the secondary streams are polled in turn. Once the primary stream is done,
+/// every secondary stream is dropped and the stream ends. More secondary streams can be added with
+/// [`SelectUntilPrimaryIsDone::push`].
+pub fn select_until_primary_is_done<St1, St2, I>(
+    primary: St1,
+    secondaries: I,
+) -> SelectUntilPrimaryIsDone<St1, St2>
+where
+    St1: Stream,
+    St2: Stream<Item = St1::Item> + Unpin,
+    I: IntoIterator<Item = St2>,
+{
+    SelectUntilPrimaryIsDone {
+        primary,
+        secondaries: select_all(secondaries),
+        last: PollNext::Right,
+        done: false,
+    }
+}
+
+//
+impl<St1, St2> SelectUntilPrimaryIsDone<St1, St2>
+where
+    St1: Stream,
+    St2: Stream<Item = St1::Item> + Unpin,
+{
+    /// Dropped at once if the primary stream is done.
+    pub fn push(&mut self, secondary: St2) {
+        if !self.done {
+            self.secondaries.push(secondary);
+        }
+    }
+
+    /// The number of secondary streams that are not done.
+    pub fn len(&self) -> usize {
+        self.secondaries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.secondaries.is_empty()
+    }
+}
+
+impl<St1, St2> SelectUntilPrimaryIsDone<St1, St2> {
+    pub fn get_ref(&self) -> (&St1, &SelectAll<St2>) {
+        (&self.primary, &self.secondaries)
+    }
+
+    pub fn get_mut(&mut self) -> (&mut St1, &mut SelectAll<St2>) {
+        (&mut self.primary, &mut self.secondaries)
+    }
+
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> (Pin<&mut St1>, &mut SelectAll<St2>) {
+        let this = self.project();
+        (this.primary, this.secondaries)
+    }
+
+    pub fn into_inner(self) -> (St1, SelectAll<St2>) {
+        (self.primary, self.secondaries)
+    }
+}
+
+//
+impl<St1, St2> FusedStream for SelectUntilPrimaryIsDone<St1, St2>
+where
+    St1: Stream,
+    St2: Stream<Item = St1::Item> + Unpin,
+{
+    fn is_terminated(&self) -> bool {
+        self.done
+    }
+}
+
+//
+impl<St1, St2> Stream for SelectUntilPrimaryIsDone<St1, St2>
+where
+    St1: Stream,
+    St2: Stream<Item = St1::Item> + Unpin,
+{
+    type Item = St1::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<St1::Item>> {
+        let mut this = self.project();
+
+        if *this.done {
+            return Poll::Ready(None);
+        }
+
+        this.last.toggle();
+        if *this.last == PollNext::Right {
+            // An empty SelectAll is done, it is not the end while the primary stream is not done.
+            if let Poll::Ready(Some(item)) = this.secondaries.poll_next_unpin(cx) {
+                return Poll::Ready(Some(item));
+            }
+        }
+
+        match this.primary.as_mut().poll_next(cx) {
+            Poll::Ready(Some(item)) => return Poll::Ready(Some(item)),
+            Poll::Ready(None) => {
+                *this.done = true;
+                this.secondaries.clear();
+                return Poll::Ready(None);
+            }
+            Poll::Pending => {}
+        }
+
+        if *this.last == PollNext::Left {
+            if let Poll::Ready(Some(item)) = this.secondaries.poll_next_unpin(cx) {
+                return Poll::Ready(Some(item));
+            }
+        }
+
+        Poll::Pending
+    }
+}
+
+//
+impl<St1, St2> fmt::Debug for SelectUntilPrimaryIsDone<St1, St2>
+where
+    St1: fmt::Debug,
+    St2: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SelectUntilPrimaryIsDone")
+            .field("primary", &self.primary)
+            .field("secondaries", &self.secondaries)
+            .field("done", &self.done)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::{boxed::Box, vec, vec::Vec};
+
+    use futures_util::stream::{self, BoxStream};
+
+    #[test]
+    fn simple() {
+        futures_executor::block_on(async {
+            for (range, ret) in [
+                (1..=1, vec![1, 0]),
+                (1..=2, vec![1, 0, 2, 0]),
+                (1..=3, vec![1, 0, 2, 0, 3, 0]),
+            ] {
+                let st1 = stream::iter(range);
+                let secondaries = [stream::repeat(0), stream::repeat(0)];
+
+                let st = select_until_primary_is_done(st1, secondaries);
+
+                assert_eq!(st.collect::<Vec<_>>().await, ret);
+            }
+        })
+    }
+
+    #[test]
+    fn test_push() {
+        futures_executor::block_on(async {
+            let st1 = stream::iter(1..=3).boxed();
+            let mut st = select_until_primary_is_done(st1, Vec::<BoxStream<'_, usize>>::new());
+            assert!(st.is_empty());
+
+            assert_eq!(st.next().await, Some(1));
+            assert_eq!(st.next().await, Some(2));
+
+            st.push(stream::iter([10, 20]).boxed());
+            st.push(Box::pin(stream::repeat(0)));
+            assert_eq!(st.len(), 2);
+
+            let rest = st.by_ref().collect::<Vec<_>>().await;
+            assert_eq!(rest.len(), 2);
+            assert_eq!(rest[0], 3);
+            assert!(st.is_terminated());
+            assert!(st.is_empty());
+
+            st.push(Box::pin(stream::repeat(0)));
+            assert!(st.is_empty());
+            assert_eq!(st.next().await, None);
+        })
+    }
+}
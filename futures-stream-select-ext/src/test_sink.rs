@@ -0,0 +1,53 @@
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_util::Sink;
+
+// Records up to 8 items without allocating, closing is pending `close_pending` times.
+#[derive(Debug, Default)]
+pub(crate) struct TestSink {
+    pub(crate) buf: [usize; 8],
+    pub(crate) len: usize,
+    pub(crate) close_pending: usize,
+    pub(crate) closed: bool,
+}
+
+impl TestSink {
+    pub(crate) fn items(&self) -> &[usize] {
+        &self.buf[..self.len]
+    }
+}
+
+impl Sink<usize> for TestSink {
+    type Error = ();
+
+    fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), ()>> {
+        assert!(!self.closed, "poll_ready after closed");
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, item: usize) -> Result<(), ()> {
+        let len = self.len;
+        *self.buf.get_mut(len).ok_or(())? = item;
+        self.len += 1;
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), ()>> {
+        assert!(!self.closed, "poll_flush after closed");
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), ()>> {
+        assert!(!self.closed, "poll_close after closed");
+        if self.close_pending > 0 {
+            self.close_pending -= 1;
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        self.closed = true;
+        Poll::Ready(Ok(()))
+    }
+}